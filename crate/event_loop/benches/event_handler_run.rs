@@ -0,0 +1,242 @@
+//! Benchmarks running an `EventHandler` once, as the event loop does per tick.
+//!
+//! Before the benchmarks run, the number of allocations per run is printed and
+//! asserted for each kind of event handler, which is zero once the first run
+//! has allocated any reused state, such as a watchdog's timer.
+
+#[cfg(feature = "watchdog")]
+use std::time::Duration;
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    future::Future,
+    pin::Pin,
+    sync::atomic::{AtomicUsize, Ordering},
+    task::{Context, Poll},
+};
+
+use criterion::{black_box, criterion_group, Criterion};
+use futures::{
+    future::{self, Ready},
+    task::noop_waker_ref,
+};
+use nginee_event_loop::{
+    EventHandler, EventHandlerResult, EventHandlingOutcome, Handler, HandlerContext,
+};
+#[cfg(feature = "watchdog")]
+use nginee_event_loop::{Watchdog, WatchdogPolicy};
+
+/// Number of runs to count allocations over.
+const RUN_COUNT: usize = 1000;
+
+/// Allocator that counts allocations.
+struct CountingAllocator;
+
+static ALLOCATION_COUNT: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATION_COUNT.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+#[derive(Debug)]
+struct Error;
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "Error")
+    }
+}
+
+impl std::error::Error for Error {}
+
+/// Handler implemented on a type.
+struct Counter(u64);
+
+impl Handler for Counter {
+    type Error = Error;
+    type Future = Ready<EventHandlerResult<Error>>;
+
+    fn run(&mut self, _context: &HandlerContext) -> Self::Future {
+        self.0 += 1;
+        black_box(self.0);
+        future::ready(Ok(EventHandlingOutcome::Continue))
+    }
+}
+
+fn event_handler_new() -> EventHandler<Error> {
+    let mut count = 0u64;
+    EventHandler::new(move || {
+        count += 1;
+        let count = black_box(count);
+        async move {
+            black_box(count);
+            Ok(EventHandlingOutcome::Continue)
+        }
+    })
+}
+
+fn event_handler_new_sync() -> EventHandler<Error> {
+    let mut count = 0u64;
+    EventHandler::new_sync(move || {
+        count += 1;
+        black_box(count);
+        Ok(EventHandlingOutcome::Continue)
+    })
+}
+
+fn event_handler_from_handler() -> EventHandler<Error> {
+    EventHandler::from(Counter(0))
+}
+
+fn event_handler_err_into() -> EventHandler<Error> {
+    event_handler_new().err_into::<Error>()
+}
+
+#[cfg(feature = "watchdog")]
+fn event_handler_with_watchdog() -> EventHandler<Error> {
+    event_handler_new().with_watchdog(Watchdog::new(
+        Duration::from_secs(60),
+        WatchdogPolicy::Warn,
+        |_| {},
+    ))
+}
+
+/// Closure whose future is boxed on every run, as event handlers used to be.
+fn boxed_per_run() -> impl FnMut() -> Pin<Box<dyn Future<Output = EventHandlerResult<Error>>>> {
+    let mut count = 0u64;
+    move || {
+        count += 1;
+        let count = black_box(count);
+        Box::pin(async move {
+            black_box(count);
+            Ok(EventHandlingOutcome::Continue)
+        })
+    }
+}
+
+/// Polls a pinned future in place until it completes.
+fn run_pinned<Fut>(mut future: Pin<&mut Fut>) -> Fut::Output
+where
+    Fut: Future + ?Sized,
+{
+    let mut context = Context::from_waker(noop_waker_ref());
+    loop {
+        if let Poll::Ready(output) = future.as_mut().poll(&mut context) {
+            return output;
+        }
+    }
+}
+
+/// Runs the event handler, without allocating to poll it.
+fn run(event_handler: &mut EventHandler<Error>) -> EventHandlerResult<Error> {
+    let future = event_handler.run();
+    futures::pin_mut!(future);
+    run_pinned(future)
+}
+
+/// Prints and asserts that the event handler does not allocate per run.
+///
+/// # Parameters
+///
+/// * `label`: Kind of event handler, used when reporting it.
+/// * `event_handler`: The event handler to run.
+fn assert_no_allocations_per_run(label: &str, mut event_handler: EventHandler<Error>) {
+    // The first run allocates any state that is reused across runs.
+    let _ = run(&mut event_handler);
+
+    let allocation_count_before = ALLOCATION_COUNT.load(Ordering::Relaxed);
+    (0..RUN_COUNT).for_each(|_| {
+        let _ = run(&mut event_handler);
+    });
+    let allocation_count = ALLOCATION_COUNT.load(Ordering::Relaxed) - allocation_count_before;
+
+    println!(
+        "{}: {} allocations per run",
+        label,
+        allocation_count as f64 / RUN_COUNT as f64
+    );
+    assert_eq!(
+        0, allocation_count,
+        "{}: unexpected allocation count over {} runs",
+        label, RUN_COUNT
+    );
+}
+
+/// Prints and asserts the number of allocations per run of each kind of event
+/// handler.
+fn allocations_per_run() {
+    assert_no_allocations_per_run("EventHandler::new", event_handler_new());
+    assert_no_allocations_per_run("EventHandler::new_sync", event_handler_new_sync());
+    assert_no_allocations_per_run("EventHandler::from(Handler)", event_handler_from_handler());
+    assert_no_allocations_per_run("EventHandler::err_into", event_handler_err_into());
+    #[cfg(feature = "watchdog")]
+    assert_no_allocations_per_run("EventHandler::with_watchdog", event_handler_with_watchdog());
+
+    let mut fn_boxed = boxed_per_run();
+    let allocation_count_before = ALLOCATION_COUNT.load(Ordering::Relaxed);
+    (0..RUN_COUNT).for_each(|_| {
+        let _ = run_pinned(fn_boxed().as_mut());
+    });
+    let allocation_count = ALLOCATION_COUNT.load(Ordering::Relaxed) - allocation_count_before;
+    println!(
+        "boxed future per run (previous): {} allocations per run",
+        allocation_count as f64 / RUN_COUNT as f64
+    );
+    assert_eq!(RUN_COUNT, allocation_count);
+}
+
+fn bench_event_handler_run(c: &mut Criterion) {
+    let mut group = c.benchmark_group("event_handler_run");
+
+    group.bench_function("boxed_future_per_run", |b| {
+        let mut fn_boxed = boxed_per_run();
+        b.iter(|| run_pinned(fn_boxed().as_mut()))
+    });
+
+    group.bench_function("new", |b| {
+        let mut event_handler = event_handler_new();
+        b.iter(|| run(&mut event_handler))
+    });
+
+    group.bench_function("new_sync", |b| {
+        let mut event_handler = event_handler_new_sync();
+        b.iter(|| run(&mut event_handler))
+    });
+
+    group.bench_function("from_handler", |b| {
+        let mut event_handler = event_handler_from_handler();
+        b.iter(|| run(&mut event_handler))
+    });
+
+    group.bench_function("err_into", |b| {
+        let mut event_handler = event_handler_err_into();
+        b.iter(|| run(&mut event_handler))
+    });
+
+    #[cfg(feature = "watchdog")]
+    group.bench_function("with_watchdog", |b| {
+        let mut event_handler = event_handler_with_watchdog();
+        b.iter(|| run(&mut event_handler))
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_event_handler_run);
+
+fn main() {
+    allocations_per_run();
+
+    benches();
+
+    Criterion::default().configure_from_args().final_summary();
+}
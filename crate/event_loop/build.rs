@@ -0,0 +1,14 @@
+//! Passes enabled features to the build scripts of dependent crates, which
+//! read them as `DEP_NGINEE_EVENT_LOOP_<FEATURE>` environment variables.
+//!
+//! This lets `nginee_test` match the event loop variant that is built, even
+//! when the feature is only enabled on `nginee_event_loop`, such as through
+//! `cargo test -p nginee_event_loop --features window`.
+
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+
+    if std::env::var_os("CARGO_FEATURE_WINDOW").is_some() {
+        println!("cargo:window=1");
+    }
+}
@@ -0,0 +1,93 @@
+use std::{error::Error, fmt};
+
+/// Error from an event handler, boxed so that event handlers with different
+/// error types may run in one event loop.
+///
+/// The original error is preserved, and may be retrieved with `downcast_ref`
+/// or `downcast`.
+#[derive(Debug)]
+pub struct BoxedError(Box<dyn Error + Send + Sync + 'static>);
+
+impl BoxedError {
+    /// Returns a new `BoxedError`.
+    ///
+    /// # Parameters
+    ///
+    /// * `error`: The error to box.
+    pub fn new<E>(error: E) -> Self
+    where
+        E: Error + Send + Sync + 'static,
+    {
+        Self(Box::new(error))
+    }
+
+    /// Returns a reference to the original error, if it is of type `T`.
+    pub fn downcast_ref<T>(&self) -> Option<&T>
+    where
+        T: Error + 'static,
+    {
+        self.0.downcast_ref::<T>()
+    }
+
+    /// Returns the original error if it is of type `T`, otherwise returns
+    /// `self`.
+    pub fn downcast<T>(self) -> Result<T, Self>
+    where
+        T: Error + 'static,
+    {
+        self.0.downcast::<T>().map(|error| *error).map_err(Self)
+    }
+
+    /// Returns the boxed original error.
+    pub fn into_inner(self) -> Box<dyn Error + Send + Sync + 'static> {
+        self.0
+    }
+}
+
+impl fmt::Display for BoxedError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl Error for BoxedError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        self.0.source()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io;
+
+    use crossbeam_channel::SendError;
+
+    use crate::BoxedError;
+
+    #[test]
+    fn downcast_ref_returns_original_error() {
+        let boxed_error = BoxedError::new(io::Error::new(
+            io::ErrorKind::ConnectionAborted,
+            "disconnected",
+        ));
+
+        assert_eq!(
+            Some(io::ErrorKind::ConnectionAborted),
+            boxed_error.downcast_ref::<io::Error>().map(io::Error::kind)
+        );
+        assert!(boxed_error.downcast_ref::<SendError<()>>().is_none());
+        assert_eq!("disconnected", boxed_error.to_string());
+    }
+
+    #[test]
+    fn downcast_returns_self_when_type_does_not_match() {
+        let boxed_error = BoxedError::new(SendError(()));
+
+        let boxed_error = boxed_error.downcast::<io::Error>().unwrap_err();
+
+        assert_eq!(
+            Some(SendError(())),
+            boxed_error.downcast::<SendError<()>>().ok()
+        );
+    }
+}
@@ -0,0 +1,97 @@
+use std::fmt;
+#[cfg(not(target_arch = "wasm32"))]
+use std::{io, path::PathBuf};
+
+/// Errors when loading or applying an `EventLoopConfig`.
+#[derive(Debug)]
+pub enum ConfigError {
+    /// Failed to read the configuration file.
+    #[cfg(not(target_arch = "wasm32"))]
+    Io {
+        /// Path to the configuration file.
+        path: PathBuf,
+        /// The underlying error.
+        error: io::Error,
+    },
+    /// Failed to fetch the configuration.
+    #[cfg(target_arch = "wasm32")]
+    Fetch {
+        /// URL of the configuration.
+        url: String,
+        /// Description of the error.
+        error: String,
+    },
+    /// The configuration is not valid RON.
+    Parse {
+        /// Path or URL of the configuration.
+        location: String,
+        /// The underlying error.
+        error: ron::de::Error,
+    },
+    /// The configuration is not valid TOML.
+    ParseToml {
+        /// Path or URL of the configuration.
+        location: String,
+        /// The underlying error.
+        error: toml::de::Error,
+    },
+    /// The configuration declares settings for an event handler that does not
+    /// exist.
+    HandlerNotFound {
+        /// Name of the event handler.
+        name: String,
+    },
+    /// The rate limit declared for an event handler is invalid.
+    #[cfg(feature = "rate_limit")]
+    RateLimit {
+        /// Name of the event handler.
+        name: String,
+        /// The underlying error.
+        error: crate::Error,
+    },
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            #[cfg(not(target_arch = "wasm32"))]
+            Self::Io { path, .. } => {
+                write!(f, "Failed to read configuration `{}`.", path.display())
+            }
+            #[cfg(target_arch = "wasm32")]
+            Self::Fetch { url, error } => {
+                write!(f, "Failed to fetch configuration `{}`: {}", url, error)
+            }
+            Self::Parse { location, .. } | Self::ParseToml { location, .. } => {
+                write!(f, "Failed to parse configuration `{}`.", location)
+            }
+            Self::HandlerNotFound { name } => write!(
+                f,
+                "Configuration declares event handler `{}`, which does not exist.",
+                name
+            ),
+            #[cfg(feature = "rate_limit")]
+            Self::RateLimit { name, .. } => write!(
+                f,
+                "Configuration declares an invalid rate limit for event handler `{}`.",
+                name
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            #[cfg(not(target_arch = "wasm32"))]
+            Self::Io { error, .. } => Some(error),
+            #[cfg(target_arch = "wasm32")]
+            Self::Fetch { .. } => None,
+            Self::Parse { error, .. } => Some(error),
+            Self::ParseToml { error, .. } => Some(error),
+            Self::HandlerNotFound { .. } => None,
+            #[cfg(feature = "rate_limit")]
+            Self::RateLimit { error, .. } => Some(error),
+        }
+    }
+}
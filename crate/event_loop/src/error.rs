@@ -1,15 +1,89 @@
-use std::fmt;
+use std::{fmt, time::Duration};
 
 /// Errors for the `nginee_event_loop` crate.
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub enum Error {
     /// The user provided zero for the FPS value.
     RateLimitFpsZero,
+    /// The user provided an FPS value above one tick per nanosecond, which
+    /// cannot be represented as a `Duration` between ticks.
+    RateLimitFpsTooHigh {
+        /// The FPS value provided.
+        fps: u32,
+    },
+    /// The user provided zero for the burst size.
+    RateLimitBurstZero,
+    /// The user set a burst size on a rate limit that is unlimited.
+    RateLimitBurstUnlimited,
+    /// The user set a minimum spacing on a rate limit that is unlimited.
+    RateLimitMinSpacingUnlimited,
+    /// The user set a jitter on a rate limit that is unlimited.
+    RateLimitJitterUnlimited,
+    /// The burst size multiplied by the rate limit period overflows a
+    /// `Duration`.
+    RateLimitBurstOverflow {
+        /// The burst size provided.
+        burst: u32,
+        /// Duration to replenish one invocation.
+        period: Duration,
+    },
+    /// The minimum spacing is longer than the rate limit period.
+    RateLimitMinSpacingExceedsPeriod {
+        /// The minimum spacing provided.
+        min_spacing: Duration,
+        /// Duration to replenish one invocation.
+        period: Duration,
+    },
+    /// The jitter is longer than the rate limit period.
+    RateLimitJitterExceedsPeriod {
+        /// The jitter provided.
+        jitter: Duration,
+        /// Duration to replenish one invocation.
+        period: Duration,
+    },
 }
 
 impl fmt::Display for Error {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "FPS must be greater than zero.")
+        match self {
+            Self::RateLimitFpsZero => write!(f, "FPS must be greater than zero."),
+            Self::RateLimitFpsTooHigh { fps } => write!(
+                f,
+                "FPS `{}` must not be greater than one tick per nanosecond.",
+                fps
+            ),
+            Self::RateLimitBurstZero => write!(f, "Burst size must be greater than zero."),
+            Self::RateLimitBurstUnlimited => write!(
+                f,
+                "Burst size cannot be set on a rate limit with a zero interval."
+            ),
+            Self::RateLimitMinSpacingUnlimited => write!(
+                f,
+                "Minimum spacing cannot be set on a rate limit with a zero interval."
+            ),
+            Self::RateLimitJitterUnlimited => write!(
+                f,
+                "Jitter cannot be set on a rate limit with a zero interval."
+            ),
+            Self::RateLimitBurstOverflow { burst, period } => write!(
+                f,
+                "Burst size `{}` is too large for the rate limit period `{:?}`.",
+                burst, period
+            ),
+            Self::RateLimitMinSpacingExceedsPeriod {
+                min_spacing,
+                period,
+            } => write!(
+                f,
+                "Minimum spacing `{:?}` must not be longer than the rate limit period `{:?}`.",
+                min_spacing, period
+            ),
+            Self::RateLimitJitterExceedsPeriod { jitter, period } => write!(
+                f,
+                "Jitter `{:?}` must not be longer than the rate limit period `{:?}`.",
+                jitter, period
+            ),
+        }
     }
 }
 
@@ -17,6 +91,8 @@ impl std::error::Error for Error {}
 
 #[cfg(test)]
 mod tests {
+    use std::time::Duration;
+
     use crate::Error;
 
     #[test]
@@ -26,4 +102,16 @@ mod tests {
             Error::RateLimitFpsZero.to_string()
         )
     }
+
+    #[test]
+    fn display_min_spacing_exceeds_period_includes_durations() {
+        assert_eq!(
+            "Minimum spacing `12ms` must not be longer than the rate limit period `11ms`.",
+            Error::RateLimitMinSpacingExceedsPeriod {
+                min_spacing: Duration::from_millis(12),
+                period: Duration::from_millis(11),
+            }
+            .to_string()
+        )
+    }
 }
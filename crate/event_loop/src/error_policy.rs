@@ -0,0 +1,31 @@
+use core::fmt::{self, Debug};
+use std::error::Error;
+
+/// What to do when an event handler returns an error.
+#[derive(Clone, Copy)]
+pub enum ErrorPolicy {
+    /// Exits the event loop with the error.
+    Exit,
+    /// Passes the event handler's name and error to the given function, and
+    /// continues the event loop.
+    ///
+    /// The event handler runs again on its next tick.
+    Skip(fn(&str, &dyn Error)),
+}
+
+#[cfg_attr(tarpaulin, skip)]
+impl Debug for ErrorPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Exit => f.write_str("Exit"),
+            Self::Skip(_) => f.debug_tuple("Skip").field(&"..").finish(),
+        }
+    }
+}
+
+#[allow(clippy::derivable_impls)] // `#[default]` on enum variants requires Rust 1.62.
+impl Default for ErrorPolicy {
+    fn default() -> Self {
+        ErrorPolicy::Exit
+    }
+}
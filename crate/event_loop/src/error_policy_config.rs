@@ -0,0 +1,33 @@
+use std::error::Error;
+
+use serde::Deserialize;
+
+use crate::ErrorPolicy;
+
+/// What to do when an event handler returns an error, declared in an
+/// `EventLoopConfig`.
+///
+/// ```ron
+/// Skip
+/// ```
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Eq)]
+pub enum ErrorPolicyConfig {
+    /// Exits the event loop with the error.
+    Exit,
+    /// Reports the error, and continues the event loop.
+    Skip,
+}
+
+impl ErrorPolicyConfig {
+    /// Returns the `ErrorPolicy` declared by this configuration.
+    ///
+    /// # Parameters
+    ///
+    /// * `reporter`: Function to report skipped errors, e.g. to a logger.
+    pub fn error_policy(self, reporter: fn(&str, &dyn Error)) -> ErrorPolicy {
+        match self {
+            Self::Exit => ErrorPolicy::Exit,
+            Self::Skip => ErrorPolicy::Skip(reporter),
+        }
+    }
+}
@@ -0,0 +1,161 @@
+use std::{
+    any::{Any, TypeId},
+    cell::RefCell,
+    collections::HashMap,
+    rc::Rc,
+};
+
+use crate::{event_channel::EventChannel, EventReader, EventWriter};
+
+/// Typed publish / subscribe channels between event handlers.
+///
+/// Event handlers emit events of a type `T` through an `EventWriter<T>`, and
+/// other event handlers read all events of that type emitted since their last
+/// run through an `EventReader<T>`.
+///
+/// Each event type has its own channel, which retains up to
+/// [`EventBus::DEFAULT_CAPACITY`] events by default. When a channel is full,
+/// its oldest event is dropped, so readers that fall behind miss events.
+///
+/// This is obtained through `EventLoop::event_bus`, and may be cloned.
+#[derive(Clone, Debug, Default)]
+pub struct EventBus {
+    /// Channel for each event type, `Rc<RefCell<EventChannel<T>>>` keyed by
+    /// the `TypeId` of `T`.
+    channels: Rc<RefCell<HashMap<TypeId, Box<dyn Any>>>>,
+}
+
+impl EventBus {
+    /// Number of events retained by each channel, unless set with
+    /// [`EventBus::set_capacity`].
+    pub const DEFAULT_CAPACITY: usize = 1024;
+
+    /// Returns a new `EventBus`.
+    pub(crate) fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns an `EventWriter` to emit events of type `T`.
+    pub fn writer<T>(&self) -> EventWriter<T>
+    where
+        T: 'static,
+    {
+        EventWriter::new(self.channel::<T>())
+    }
+
+    /// Returns an `EventReader` to read events of type `T`.
+    ///
+    /// The reader receives events emitted after this call.
+    pub fn reader<T>(&self) -> EventReader<T>
+    where
+        T: 'static,
+    {
+        EventReader::new(self.channel::<T>())
+    }
+
+    /// Sets the number of events of type `T` to retain.
+    ///
+    /// # Parameters
+    ///
+    /// * `capacity`: Maximum number of events to retain.
+    pub fn set_capacity<T>(&self, capacity: usize)
+    where
+        T: 'static,
+    {
+        self.channel::<T>().borrow_mut().set_capacity(capacity);
+    }
+
+    /// Returns the channel for events of type `T`, creating it if necessary.
+    fn channel<T>(&self) -> Rc<RefCell<EventChannel<T>>>
+    where
+        T: 'static,
+    {
+        self.channels
+            .borrow_mut()
+            .entry(TypeId::of::<T>())
+            .or_insert_with(|| {
+                Box::new(Rc::new(RefCell::new(EventChannel::<T>::new(
+                    Self::DEFAULT_CAPACITY,
+                ))))
+            })
+            .downcast_ref::<Rc<RefCell<EventChannel<T>>>>()
+            .expect("Channel is keyed by its event type.")
+            .clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::stream::StreamExt;
+
+    use super::EventBus;
+
+    #[test]
+    fn reader_reads_events_emitted_since_last_read() {
+        let event_bus = EventBus::new();
+        let writer = event_bus.writer::<u32>();
+        writer.emit(0);
+
+        let mut reader = event_bus.reader::<u32>();
+        writer.emit_all(vec![1, 2]);
+
+        assert!(reader.has_events());
+        assert_eq!(vec![1, 2], reader.read());
+        assert!(!reader.has_events());
+
+        writer.emit(3);
+
+        assert_eq!(vec![3], reader.read());
+    }
+
+    #[test]
+    fn readers_have_independent_cursors() {
+        let event_bus = EventBus::new();
+        let writer = event_bus.writer::<u32>();
+        let mut reader_0 = event_bus.reader::<u32>();
+        let mut reader_1 = event_bus.reader::<u32>();
+
+        writer.emit(1);
+        assert_eq!(vec![1], reader_0.read());
+
+        writer.emit(2);
+        assert_eq!(vec![2], reader_0.read());
+        assert_eq!(vec![1, 2], reader_1.read());
+    }
+
+    #[test]
+    fn channels_are_separated_by_event_type() {
+        let event_bus = EventBus::new();
+        let mut reader_u32 = event_bus.reader::<u32>();
+        let mut reader_string = event_bus.reader::<String>();
+
+        event_bus.writer::<u32>().emit(1);
+
+        assert_eq!(vec![1], reader_u32.read());
+        assert!(reader_string.read().is_empty());
+    }
+
+    #[test]
+    fn reader_streams_events_one_at_a_time() {
+        let event_bus = EventBus::new();
+        let writer = event_bus.writer::<u32>();
+        let mut reader = event_bus.reader::<u32>();
+
+        writer.emit_all(vec![1, 2]);
+
+        assert_eq!(Some(1), smol::run(reader.next()));
+        assert_eq!(vec![2], reader.read());
+    }
+
+    #[test]
+    fn oldest_events_are_dropped_beyond_capacity() {
+        let event_bus = EventBus::new();
+        event_bus.set_capacity::<u32>(2);
+        let writer = event_bus.writer::<u32>();
+        let mut reader = event_bus.reader::<u32>();
+
+        writer.emit_all(vec![1, 2, 3]);
+
+        assert_eq!(vec![2, 3], reader.read());
+    }
+}
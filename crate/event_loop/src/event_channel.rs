@@ -0,0 +1,92 @@
+use std::{collections::VecDeque, task::Waker};
+
+/// Events of one type, retained for readers up to a capacity.
+///
+/// # Type Parameters
+///
+/// * `T`: Event type.
+#[derive(Debug)]
+pub(crate) struct EventChannel<T> {
+    /// Retained events, oldest first.
+    events: VecDeque<T>,
+    /// Maximum number of events to retain.
+    capacity: usize,
+    /// Sequence number of the oldest retained event.
+    first: u64,
+    /// Wakers of readers that are waiting for an event.
+    wakers: Vec<Waker>,
+}
+
+impl<T> EventChannel<T> {
+    /// Returns a new `EventChannel`.
+    ///
+    /// # Parameters
+    ///
+    /// * `capacity`: Maximum number of events to retain.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            events: VecDeque::new(),
+            capacity,
+            first: 0,
+            wakers: Vec::new(),
+        }
+    }
+
+    /// Sets the maximum number of events to retain, dropping the oldest events
+    /// beyond it.
+    pub fn set_capacity(&mut self, capacity: usize) {
+        self.capacity = capacity;
+        self.trim(capacity);
+    }
+
+    /// Adds an event, dropping the oldest event if the channel is full.
+    ///
+    /// Readers that are waiting for an event are woken.
+    pub fn emit(&mut self, event: T) {
+        if self.capacity == 0 {
+            self.first += 1;
+        } else {
+            self.trim(self.capacity - 1);
+            self.events.push_back(event);
+        }
+
+        self.wakers.drain(..).for_each(Waker::wake);
+    }
+
+    /// Registers a reader's waker, to be woken when an event is emitted.
+    pub fn register(&mut self, waker: &Waker) {
+        if !self
+            .wakers
+            .iter()
+            .any(|waker_existing| waker_existing.will_wake(waker))
+        {
+            self.wakers.push(waker.clone());
+        }
+    }
+
+    /// Returns the sequence number of the oldest retained event.
+    pub fn first(&self) -> u64 {
+        self.first
+    }
+
+    /// Returns the sequence number of the next event to be emitted.
+    pub fn end(&self) -> u64 {
+        self.first + self.events.len() as u64
+    }
+
+    /// Returns the retained events from the given sequence number onwards.
+    ///
+    /// Events that have been dropped are skipped.
+    pub fn events_since(&self, sequence: u64) -> impl Iterator<Item = &T> {
+        let skip = sequence.saturating_sub(self.first) as usize;
+        self.events.iter().skip(skip)
+    }
+
+    /// Drops the oldest events until at most `len` remain.
+    fn trim(&mut self, len: usize) {
+        while self.events.len() > len {
+            self.events.pop_front();
+            self.first += 1;
+        }
+    }
+}
@@ -1,53 +1,289 @@
 use core::{
     fmt::{self, Debug},
     future::Future,
-    marker::PhantomData,
     pin::Pin,
+    task::{Context, Poll, Waker},
 };
-use std::error::Error;
+use std::{
+    any,
+    borrow::Cow,
+    error::Error,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex, MutexGuard, PoisonError,
+    },
+};
+
+use futures::{
+    future::{self, Fuse, FusedFuture, FutureExt},
+    stream::{self, FusedStream, Stream, StreamExt},
+    task::{self, ArcWake, AtomicWaker},
+};
+use instant::Instant;
 
-use crate::EventHandlerResult;
+#[cfg(all(feature = "rate_limit", feature = "window"))]
+use crate::FramePacing;
 #[cfg(feature = "rate_limit")]
 use crate::RateLimit;
+#[cfg(feature = "watchdog")]
+use crate::{watchdog::WatchdogTimer, Watchdog};
+use crate::{
+    BoxedError, ErrorPolicy, EventHandlerResult, EventHandlingOutcome, Frames, Handler,
+    HandlerContext,
+};
+#[cfg(feature = "config")]
+use crate::{ConfigError, HandlerConfig};
 
 /// Wrapper type for event handler logic.
 pub struct EventHandler<E> {
     /// Event handler logic.
     fn_handler_logic: Box<dyn EventHandlerLogic<E>>,
+    /// Name of this event handler, used when reporting it.
+    name: Cow<'static, str>,
+    /// Groups this event handler belongs to, which may be disabled at runtime.
+    groups: Vec<Cow<'static, str>>,
+    /// What to do when this event handler returns an error.
+    error_policy: ErrorPolicy,
+    /// When this event handler's logic last started running, shared with the
+    /// event loop's `Introspection`.
+    last_run: Arc<Mutex<Option<Instant>>>,
     #[cfg(feature = "rate_limit")]
     /// Rate to limit this event handler's execution.
     pub rate_limit: Option<RateLimit>,
+    #[cfg(all(feature = "rate_limit", feature = "window"))]
+    /// Paces this event handler to the display's refresh, taking precedence
+    /// over `rate_limit`.
+    pub frame_pacing: Option<FramePacing>,
+    #[cfg(feature = "watchdog")]
+    /// Detects when this event handler runs for too long.
+    pub watchdog: Option<Watchdog<E>>,
+    #[cfg(feature = "watchdog")]
+    /// Timer the watchdog uses to watch each run.
+    watchdog_timer: WatchdogTimer,
 }
 
 impl<E> EventHandler<E>
 where
-    E: Error + Send + 'static,
+    E: Error + 'static,
 {
     /// Returns a new `EventHandler`.
     ///
+    /// The event handler is named after the type of `fn_handler_logic`.
+    ///
+    /// The future does not need to be `Send`, as event handlers are run on the
+    /// thread that runs the event loop. This allows event handlers to use
+    /// `!Send` types, such as `wasm_bindgen_futures::JsFuture`, or windowing
+    /// objects that must stay on the main thread.
+    ///
     /// # Parameters
     ///
     /// * `handler_logic`: The logic to run.
     pub fn new<FnFut, Fut>(fn_handler_logic: FnFut) -> Self
     where
-        Fut: Future<Output = EventHandlerResult<E>> + Send + 'static,
+        Fut: Future<Output = EventHandlerResult<E>> + 'static,
         FnFut: FnMut() -> Fut + 'static,
     {
-        let fn_handler_logic = {
-            let event_handler_logic = EventHandlerLogicBasic {
-                fn_handler_logic,
-                marker: PhantomData,
-            };
-            Box::new(event_handler_logic)
+        let event_handler_logic = EventHandlerLogicBasic {
+            fn_handler_logic,
+            handler_task: Box::pin(Fuse::terminated()),
         };
 
-        Self {
+        Self::from_logic(
+            event_handler_logic,
+            Cow::Borrowed(any::type_name::<FnFut>()),
+        )
+    }
+
+    /// Returns a new `EventHandler` for synchronous logic.
+    ///
+    /// Unlike `new`, no future is created for each run. A watchdog cannot
+    /// interrupt synchronous logic, so it does not apply to these event
+    /// handlers.
+    ///
+    /// The event handler is named after the type of `fn_handler_logic`.
+    ///
+    /// # Parameters
+    ///
+    /// * `handler_logic`: The logic to run.
+    pub fn new_sync<FnSync>(fn_handler_logic: FnSync) -> Self
+    where
+        FnSync: FnMut() -> EventHandlerResult<E> + 'static,
+    {
+        Self::from_logic(
+            EventHandlerLogicSync { fn_handler_logic },
+            Cow::Borrowed(any::type_name::<FnSync>()),
+        )
+    }
+
+    /// Returns a new `EventHandler` that runs once for each item of a stream.
+    ///
+    /// The event handler is only run when the stream has an item, so the event
+    /// loop may sleep while every event handler is waiting for events. This
+    /// suits event handlers that react to messages, such as network packets or
+    /// file changes. Once the stream ends, the event handler is not run again,
+    /// and the event loop exits when every event handler has ended.
+    ///
+    /// The event handler is named after the type of `fn_handler_logic`.
+    ///
+    /// # Parameters
+    ///
+    /// * `stream`: Stream of items to handle.
+    /// * `fn_handler_logic`: The logic to run for each item.
+    pub fn from_stream<S, FnFut, Fut>(stream: S, fn_handler_logic: FnFut) -> Self
+    where
+        S: Stream + 'static,
+        Fut: Future<Output = EventHandlerResult<E>> + 'static,
+        FnFut: FnMut(S::Item) -> Fut + 'static,
+    {
+        let event_handler_logic = EventHandlerLogicStream {
+            stream: Box::pin(stream.fuse()),
             fn_handler_logic,
+            item: None,
+            handler_task: Box::pin(Fuse::terminated()),
+        };
+
+        Self::from_logic(
+            event_handler_logic,
+            Cow::Borrowed(any::type_name::<FnFut>()),
+        )
+    }
+
+    /// Returns a new `EventHandler` for a coroutine that spans frames.
+    ///
+    /// The coroutine is started once, and is resumed on each run of the event
+    /// handler until it awaits a frame boundary from `Frames`, or any other
+    /// pending future. Each run continues the event loop until the coroutine
+    /// completes, which then returns the coroutine's result. After completing
+    /// with `Continue`, the event handler has ended and is not run again, and
+    /// the event loop exits when every event handler has ended.
+    ///
+    /// While the coroutine awaits a frame count, it is resumed on every run.
+    /// While it awaits any other future, such as `Frames::wait`, the event
+    /// handler is not run until that future wakes it.
+    ///
+    /// This allows multi-frame sequences, such as cutscenes, to be written
+    /// without hand-written state machines.
+    ///
+    /// The event handler is named after the type of `fn_coroutine`.
+    ///
+    /// # Parameters
+    ///
+    /// * `fn_coroutine`: Function that starts the coroutine.
+    pub fn new_coroutine<FnCoroutine, Fut>(fn_coroutine: FnCoroutine) -> Self
+    where
+        Fut: Future<Output = EventHandlerResult<E>> + 'static,
+        FnCoroutine: FnOnce(Frames) -> Fut + 'static,
+    {
+        let frames = Frames::new();
+        let coroutine = Coroutine {
+            coroutine: Box::pin(fn_coroutine(frames.clone()).fuse()),
+            event_waker: Arc::default(),
+        };
+
+        let event_handler_logic = EventHandlerLogicCoroutine {
+            coroutine,
+            frames,
+            is_started: false,
+        };
+
+        Self::from_logic(
+            event_handler_logic,
+            Cow::Borrowed(any::type_name::<FnCoroutine>()),
+        )
+    }
+
+    /// Returns a new `EventHandler` for the given logic, with default settings.
+    ///
+    /// # Parameters
+    ///
+    /// * `event_handler_logic`: The logic to run.
+    /// * `name`: Name of the event handler, used when reporting it.
+    fn from_logic<L>(event_handler_logic: L, name: Cow<'static, str>) -> Self
+    where
+        L: EventHandlerLogic<E> + 'static,
+    {
+        Self {
+            fn_handler_logic: Box::new(event_handler_logic),
+            name,
+            groups: Vec::new(),
+            error_policy: ErrorPolicy::Exit,
+            last_run: Arc::default(),
             #[cfg(feature = "rate_limit")]
             rate_limit: None,
+            #[cfg(all(feature = "rate_limit", feature = "window"))]
+            frame_pacing: None,
+            #[cfg(feature = "watchdog")]
+            watchdog: None,
+            #[cfg(feature = "watchdog")]
+            watchdog_timer: WatchdogTimer::default(),
         }
     }
 
+    /// Sets the name of this event handler.
+    pub fn with_name<N>(mut self, name: N) -> Self
+    where
+        N: Into<Cow<'static, str>>,
+    {
+        self.name = name.into();
+        self
+    }
+
+    /// Returns the name of this event handler.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Adds this event handler to a group, which may be disabled at runtime
+    /// through `HandlerGroups`.
+    ///
+    /// An event handler may belong to multiple groups, and is disabled while
+    /// any of them is disabled.
+    ///
+    /// # Parameters
+    ///
+    /// * `group`: Name of the group, such as `"gameplay"`.
+    pub fn with_group<G>(mut self, group: G) -> Self
+    where
+        G: Into<Cow<'static, str>>,
+    {
+        self.groups.push(group.into());
+        self
+    }
+
+    /// Returns the groups this event handler belongs to.
+    pub fn groups(&self) -> &[Cow<'static, str>] {
+        &self.groups
+    }
+
+    /// Returns when this event handler's logic last started running.
+    ///
+    /// This is `None` until the event handler first runs.
+    pub fn last_run(&self) -> Option<Instant> {
+        *lock(&self.last_run)
+    }
+
+    /// Returns the handle that records when this event handler last ran.
+    pub(crate) fn last_run_handle(&self) -> Arc<Mutex<Option<Instant>>> {
+        self.last_run.clone()
+    }
+
+    /// Sets what to do when this event handler returns an error.
+    ///
+    /// Defaults to `ErrorPolicy::Exit`.
+    ///
+    /// # Parameters
+    ///
+    /// * `error_policy`: What to do when this event handler returns an error.
+    pub fn with_error_policy(mut self, error_policy: ErrorPolicy) -> Self {
+        self.error_policy = error_policy;
+        self
+    }
+
+    /// Returns what to do when this event handler returns an error.
+    pub fn error_policy(&self) -> ErrorPolicy {
+        self.error_policy
+    }
+
     /// Sets the rate limit for this event handler.
     #[cfg(feature = "rate_limit")]
     pub fn with_rate_limit(mut self, rate_limit: RateLimit) -> Self {
@@ -55,46 +291,836 @@ where
         self
     }
 
+    /// Sets the frame pacing for this event handler.
+    ///
+    /// This is intended for render event handlers, and takes precedence over
+    /// the rate limit.
+    #[cfg(all(feature = "rate_limit", feature = "window"))]
+    pub fn with_frame_pacing(mut self, frame_pacing: FramePacing) -> Self {
+        self.frame_pacing = Some(frame_pacing);
+        self
+    }
+
+    /// Sets the watchdog for this event handler.
+    #[cfg(feature = "watchdog")]
+    pub fn with_watchdog(mut self, watchdog: Watchdog<E>) -> Self {
+        self.watchdog = Some(watchdog);
+        self
+    }
+
+    /// Converts this event handler's errors into another error type.
+    ///
+    /// This allows event handlers with different error types to run in one
+    /// event loop.
+    pub fn err_into<E2>(self) -> EventHandler<E2>
+    where
+        E: Into<E2>,
+        E2: Error + 'static,
+    {
+        self.map_err_fn(Into::into)
+    }
+
+    /// Boxes this event handler's errors, preserving the original error for
+    /// downcasting.
+    ///
+    /// This allows event handlers with different error types to run in one
+    /// event loop.
+    pub fn boxed_err(self) -> EventHandler<BoxedError>
+    where
+        E: Send + Sync,
+    {
+        self.map_err_fn(BoxedError::new)
+    }
+
+    fn map_err_fn<E2>(self, fn_map_err: fn(E) -> E2) -> EventHandler<E2>
+    where
+        E2: Error + 'static,
+    {
+        let name = self.name.clone();
+        let groups = self.groups.clone();
+        let error_policy = self.error_policy;
+        let last_run = self.last_run.clone();
+        #[cfg(feature = "rate_limit")]
+        let rate_limit = self.rate_limit;
+        #[cfg(all(feature = "rate_limit", feature = "window"))]
+        let frame_pacing = self.frame_pacing;
+
+        let event_handler_logic = EventHandlerLogicMapErr {
+            event_handler: self,
+            fn_map_err,
+        };
+
+        EventHandler {
+            groups,
+            error_policy,
+            last_run,
+            #[cfg(feature = "rate_limit")]
+            rate_limit,
+            #[cfg(all(feature = "rate_limit", feature = "window"))]
+            frame_pacing,
+            ..EventHandler::from_logic(event_handler_logic, name)
+        }
+    }
+
+    /// Overrides this event handler's settings with those declared in a
+    /// configuration.
+    ///
+    /// # Parameters
+    ///
+    /// * `handler_config`: Settings of this event handler.
+    /// * `error_reporter`: Function to report errors of an event handler
+    ///   configured to skip them.
+    #[cfg(feature = "config")]
+    pub(crate) fn apply_config(
+        &mut self,
+        handler_config: &HandlerConfig,
+        error_reporter: fn(&str, &dyn Error),
+    ) -> Result<(), ConfigError> {
+        self.groups.extend(
+            handler_config
+                .groups
+                .iter()
+                .map(|group| Cow::Owned(group.clone())),
+        );
+
+        if let Some(error_policy_config) = handler_config.error_policy {
+            self.error_policy = error_policy_config.error_policy(error_reporter);
+        }
+
+        #[cfg(feature = "rate_limit")]
+        {
+            if let Some(rate_limit_config) = handler_config.rate_limit.as_ref() {
+                let rate_limit =
+                    rate_limit_config
+                        .rate_limit()
+                        .map_err(|error| ConfigError::RateLimit {
+                            name: String::from(self.name()),
+                            error,
+                        })?;
+                self.rate_limit = Some(rate_limit);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Runs the handler's exit hook, when the event loop exits.
+    pub(crate) fn on_exit(&mut self) {
+        self.fn_handler_logic.on_exit();
+    }
+
+    /// Returns whether this event handler only runs when it has an event.
+    pub(crate) fn is_event_driven(&self) -> bool {
+        self.fn_handler_logic.is_event_driven()
+    }
+
+    /// Returns whether this event handler has ended, such as when its stream
+    /// has ended or its coroutine has completed, and so is not run again.
+    pub(crate) fn is_ended(&self) -> bool {
+        self.fn_handler_logic.is_ended()
+    }
+
+    /// Returns `Ready` if this event handler has work to do, otherwise
+    /// registers the waker to be woken when it does.
+    ///
+    /// Event handlers that are not event driven are always ready. Event
+    /// handlers that have ended are ready, so that the event loop wakes to
+    /// stop waiting for them.
+    pub(crate) fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<()> {
+        self.fn_handler_logic.poll_ready(cx)
+    }
+
     /// Runs the event handler logic.
+    ///
+    /// Each event handler reuses one allocation for its future across runs,
+    /// and a watchdog reuses its timer, so steady state runs do not allocate.
+    /// `new_sync` closures do not allocate a future at all.
+    ///
+    /// When the error policy is `ErrorPolicy::Skip`, errors are passed to its
+    /// reporter, and `EventHandlingOutcome::Continue` is returned instead.
     pub async fn run(&mut self) -> EventHandlerResult<E> {
-        self.fn_handler_logic.handler_task().await
+        let event_handler_result = self.run_logic().await;
+
+        match (event_handler_result, self.error_policy) {
+            (Err(error), ErrorPolicy::Skip(reporter)) => {
+                reporter(&self.name, &error);
+                Ok(EventHandlingOutcome::Continue)
+            }
+            (event_handler_result, _) => event_handler_result,
+        }
+    }
+
+    /// Runs the event handler logic, returning its result.
+    async fn run_logic(&mut self) -> EventHandlerResult<E> {
+        if self.is_event_driven() {
+            let is_ready = future::poll_fn(|cx| Poll::Ready(self.poll_ready(cx).is_ready())).await;
+            if !is_ready {
+                return Ok(EventHandlingOutcome::Continue);
+            }
+        }
+
+        *lock(&self.last_run) = Some(Instant::now());
+
+        match self.start_logic() {
+            HandlerTask::Ready(event_handler_result) => event_handler_result,
+            HandlerTask::Pending => future::poll_fn(|cx| self.poll_logic(cx)).await,
+        }
+    }
+
+    /// Starts this run of the event handler logic, and watches it if there is
+    /// a watchdog.
+    fn start_logic(&mut self) -> HandlerTask<E> {
+        let handler_task = self.fn_handler_logic.handler_task();
+
+        #[cfg(feature = "watchdog")]
+        {
+            if let (HandlerTask::Pending, Some(watchdog)) = (&handler_task, self.watchdog.as_ref())
+            {
+                self.watchdog_timer.start(watchdog.timeout());
+            }
+        }
+
+        handler_task
+    }
+
+    /// Polls this run of the event handler logic, applying the watchdog's
+    /// policy if the run exceeds its timeout.
+    fn poll_logic(&mut self, cx: &mut Context<'_>) -> Poll<EventHandlerResult<E>> {
+        if let Poll::Ready(event_handler_result) = self.fn_handler_logic.poll_handler_task(cx) {
+            #[cfg(feature = "watchdog")]
+            self.watchdog_timer.stop();
+
+            return Poll::Ready(event_handler_result);
+        }
+
+        #[cfg(feature = "watchdog")]
+        {
+            if let Some(watchdog) = self.watchdog.as_ref() {
+                if let Poll::Ready(elapsed) = self.watchdog_timer.poll_elapsed(cx) {
+                    if let Some(event_handler_result) = watchdog.timed_out(&self.name, elapsed) {
+                        // Drops the handler task, as the watchdog cancelled it.
+                        self.fn_handler_logic.reset();
+
+                        return Poll::Ready(event_handler_result);
+                    }
+                }
+            }
+        }
+
+        Poll::Pending
+    }
+}
+
+impl<H> From<H> for EventHandler<H::Error>
+where
+    H: Handler,
+{
+    fn from(handler: H) -> Self {
+        let name = Cow::Owned(String::from(handler.name()));
+        #[cfg(feature = "rate_limit")]
+        let rate_limit = handler.rate_limit();
+
+        let event_handler_logic = EventHandlerLogicHandler {
+            handler,
+            context: HandlerContext::new(),
+            is_started: false,
+            handler_task: Box::pin(Fuse::terminated()),
+        };
+
+        Self {
+            #[cfg(feature = "rate_limit")]
+            rate_limit,
+            ..Self::from_logic(event_handler_logic, name)
+        }
     }
 }
 
+/// Locks the mutex, recovering the value if another thread panicked.
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
 #[cfg_attr(tarpaulin, skip)]
 impl<E> Debug for EventHandler<E> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         let mut debug_struct = f.debug_struct("EventHandler");
 
         debug_struct.field("fn_handler_logic", &"..");
+        debug_struct.field("name", &self.name);
+        debug_struct.field("groups", &self.groups);
+        debug_struct.field("error_policy", &self.error_policy);
+        debug_struct.field("last_run", &*lock(&self.last_run));
 
         #[cfg(feature = "rate_limit")]
         debug_struct.field("rate_limit", &self.rate_limit);
 
+        #[cfg(all(feature = "rate_limit", feature = "window"))]
+        debug_struct.field("frame_pacing", &self.frame_pacing);
+
+        #[cfg(feature = "watchdog")]
+        debug_struct.field("watchdog", &self.watchdog);
+
         debug_struct.finish()
     }
 }
 
+/// Result of starting an event handler's logic.
+enum HandlerTask<E> {
+    /// The logic ran synchronously.
+    Ready(EventHandlerResult<E>),
+    /// The logic must be polled through `EventHandlerLogic::poll_handler_task`,
+    /// and is stored in a reused allocation.
+    Pending,
+}
+
 trait EventHandlerLogic<E> {
-    fn handler_task(
-        &mut self,
-    ) -> Pin<Box<dyn Future<Output = EventHandlerResult<E>> + Send + 'static>>;
+    /// Starts this run of the logic.
+    fn handler_task(&mut self) -> HandlerTask<E>;
+
+    /// Polls the handler task started by `handler_task`.
+    fn poll_handler_task(&mut self, cx: &mut Context<'_>) -> Poll<EventHandlerResult<E>>;
+
+    /// Drops the handler task, if it has not completed.
+    #[cfg(feature = "watchdog")]
+    fn reset(&mut self) {}
+
+    fn on_exit(&mut self) {}
+
+    fn is_event_driven(&self) -> bool {
+        false
+    }
+
+    fn is_ended(&self) -> bool {
+        false
+    }
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<()> {
+        Poll::Ready(())
+    }
 }
 
 struct EventHandlerLogicBasic<FnFut, Fut> {
     fn_handler_logic: FnFut,
-    marker: PhantomData<Fut>,
+    /// Slot for the handler task, allocated once and reused for every run.
+    handler_task: Pin<Box<Fuse<Fut>>>,
 }
 
 impl<E, FnFut, Fut> EventHandlerLogic<E> for EventHandlerLogicBasic<FnFut, Fut>
 where
-    E: Send + 'static,
-    Fut: Future<Output = EventHandlerResult<E>> + Send + 'static,
+    E: 'static,
+    Fut: Future<Output = EventHandlerResult<E>> + 'static,
     FnFut: FnMut() -> Fut,
 {
-    fn handler_task(
-        &mut self,
-    ) -> Pin<Box<dyn Future<Output = EventHandlerResult<E>> + Send + 'static>> {
-        Box::pin((self.fn_handler_logic)())
+    fn handler_task(&mut self) -> HandlerTask<E> {
+        self.handler_task.set((self.fn_handler_logic)().fuse());
+        HandlerTask::Pending
+    }
+
+    fn poll_handler_task(&mut self, cx: &mut Context<'_>) -> Poll<EventHandlerResult<E>> {
+        self.handler_task.as_mut().poll(cx)
+    }
+
+    #[cfg(feature = "watchdog")]
+    fn reset(&mut self) {
+        self.handler_task.set(Fuse::terminated());
+    }
+}
+
+struct EventHandlerLogicSync<FnSync> {
+    fn_handler_logic: FnSync,
+}
+
+impl<E, FnSync> EventHandlerLogic<E> for EventHandlerLogicSync<FnSync>
+where
+    FnSync: FnMut() -> EventHandlerResult<E>,
+{
+    fn handler_task(&mut self) -> HandlerTask<E> {
+        HandlerTask::Ready((self.fn_handler_logic)())
+    }
+
+    fn poll_handler_task(&mut self, _cx: &mut Context<'_>) -> Poll<EventHandlerResult<E>> {
+        // The logic completes in `handler_task`, so there is no task to poll.
+        Poll::Ready(Ok(EventHandlingOutcome::Continue))
+    }
+}
+
+struct EventHandlerLogicStream<S, FnFut, Fut>
+where
+    S: Stream,
+{
+    stream: Pin<Box<stream::Fuse<S>>>,
+    fn_handler_logic: FnFut,
+    /// Item received from the stream, which is handled on the next run.
+    item: Option<S::Item>,
+    /// Slot for the handler task, allocated once and reused for every item.
+    handler_task: Pin<Box<Fuse<Fut>>>,
+}
+
+impl<E, S, FnFut, Fut> EventHandlerLogic<E> for EventHandlerLogicStream<S, FnFut, Fut>
+where
+    E: 'static,
+    S: Stream,
+    Fut: Future<Output = EventHandlerResult<E>> + 'static,
+    FnFut: FnMut(S::Item) -> Fut,
+{
+    fn handler_task(&mut self) -> HandlerTask<E> {
+        match self.item.take() {
+            Some(item) => {
+                self.handler_task.set((self.fn_handler_logic)(item).fuse());
+                HandlerTask::Pending
+            }
+            None => HandlerTask::Ready(Ok(EventHandlingOutcome::Continue)),
+        }
+    }
+
+    fn poll_handler_task(&mut self, cx: &mut Context<'_>) -> Poll<EventHandlerResult<E>> {
+        self.handler_task.as_mut().poll(cx)
+    }
+
+    #[cfg(feature = "watchdog")]
+    fn reset(&mut self) {
+        self.handler_task.set(Fuse::terminated());
+    }
+
+    fn is_event_driven(&self) -> bool {
+        true
+    }
+
+    fn is_ended(&self) -> bool {
+        self.item.is_none() && self.stream.is_terminated()
+    }
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<()> {
+        if self.item.is_none() {
+            // The stream is fused, so once it has ended, it keeps returning `None`.
+            match self.stream.as_mut().poll_next(cx) {
+                Poll::Ready(Some(item)) => self.item = Some(item),
+                Poll::Ready(None) => {}
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+
+        Poll::Ready(())
+    }
+}
+
+struct EventHandlerLogicCoroutine<Fut> {
+    coroutine: Coroutine<Fut>,
+    frames: Frames,
+    is_started: bool,
+}
+
+impl<E, Fut> EventHandlerLogic<E> for EventHandlerLogicCoroutine<Fut>
+where
+    Fut: Future<Output = EventHandlerResult<E>> + 'static,
+{
+    fn handler_task(&mut self) -> HandlerTask<E> {
+        if self.is_started {
+            self.frames.advance();
+        } else {
+            self.is_started = true;
+        }
+
+        HandlerTask::Pending
+    }
+
+    fn poll_handler_task(&mut self, cx: &mut Context<'_>) -> Poll<EventHandlerResult<E>> {
+        Pin::new(&mut self.coroutine).poll(cx)
+    }
+
+    fn is_event_driven(&self) -> bool {
+        true
+    }
+
+    fn is_ended(&self) -> bool {
+        self.coroutine.coroutine.is_terminated()
+    }
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<()> {
+        if !self.is_started
+            || self.frames.is_waiting_frames()
+            || self.coroutine.coroutine.is_terminated()
+        {
+            return Poll::Ready(());
+        }
+
+        self.coroutine.event_waker.poll_woken(cx)
+    }
+}
+
+/// Resumes a coroutine once per run, so that each run completes even when the
+/// coroutine is waiting.
+struct Coroutine<Fut> {
+    /// The coroutine, which does nothing once completed.
+    coroutine: Pin<Box<Fuse<Fut>>>,
+    /// Waker passed to the coroutine, which records whether the future it is
+    /// waiting on is ready.
+    event_waker: Arc<CoroutineWaker>,
+}
+
+impl<E, Fut> Future for Coroutine<Fut>
+where
+    Fut: Future<Output = EventHandlerResult<E>>,
+{
+    type Output = EventHandlerResult<E>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        self.event_waker.reset(cx.waker());
+
+        let waker = task::waker(self.event_waker.clone());
+        let mut coroutine_cx = Context::from_waker(&waker);
+        match self.coroutine.as_mut().poll(&mut coroutine_cx) {
+            Poll::Ready(event_handler_result) => Poll::Ready(event_handler_result),
+            // The event loop continues, and the coroutine is resumed when it is woken, or on
+            // the next frame if it is waiting for frames.
+            Poll::Pending => Poll::Ready(Ok(EventHandlingOutcome::Continue)),
+        }
+    }
+}
+
+/// Records whether a waiting coroutine has been woken, and wakes the event
+/// loop when it is.
+#[derive(Debug, Default)]
+struct CoroutineWaker {
+    /// Whether the coroutine has been woken since it was last resumed.
+    is_woken: AtomicBool,
+    /// Waker of the event loop.
+    waker: AtomicWaker,
+}
+
+impl CoroutineWaker {
+    /// Clears the woken flag before the coroutine is resumed.
+    fn reset(&self, waker: &Waker) {
+        self.is_woken.store(false, Ordering::Release);
+        self.waker.register(waker);
+    }
+
+    /// Returns `Ready` if the coroutine has been woken, otherwise registers the
+    /// waker to be woken when it is.
+    fn poll_woken(&self, cx: &mut Context<'_>) -> Poll<()> {
+        self.waker.register(cx.waker());
+
+        if self.is_woken.load(Ordering::Acquire) {
+            Poll::Ready(())
+        } else {
+            Poll::Pending
+        }
+    }
+}
+
+impl ArcWake for CoroutineWaker {
+    fn wake_by_ref(arc_self: &Arc<Self>) {
+        arc_self.is_woken.store(true, Ordering::Release);
+        arc_self.waker.wake();
+    }
+}
+
+/// Maps the errors of an event handler, which keeps its own watchdog.
+///
+/// Only the error policy of the mapped event handler is applied, to the mapped
+/// error.
+struct EventHandlerLogicMapErr<E, E2> {
+    event_handler: EventHandler<E>,
+    fn_map_err: fn(E) -> E2,
+}
+
+impl<E, E2> EventHandlerLogic<E2> for EventHandlerLogicMapErr<E, E2>
+where
+    E: Error + 'static,
+{
+    fn handler_task(&mut self) -> HandlerTask<E2> {
+        match self.event_handler.start_logic() {
+            HandlerTask::Ready(event_handler_result) => {
+                HandlerTask::Ready(event_handler_result.map_err(self.fn_map_err))
+            }
+            HandlerTask::Pending => HandlerTask::Pending,
+        }
+    }
+
+    fn poll_handler_task(&mut self, cx: &mut Context<'_>) -> Poll<EventHandlerResult<E2>> {
+        let fn_map_err = self.fn_map_err;
+        self.event_handler
+            .poll_logic(cx)
+            .map(|event_handler_result| event_handler_result.map_err(fn_map_err))
+    }
+
+    #[cfg(feature = "watchdog")]
+    fn reset(&mut self) {
+        self.event_handler.fn_handler_logic.reset();
+        self.event_handler.watchdog_timer.stop();
+    }
+
+    fn on_exit(&mut self) {
+        self.event_handler.on_exit();
+    }
+
+    fn is_event_driven(&self) -> bool {
+        self.event_handler.is_event_driven()
+    }
+
+    fn is_ended(&self) -> bool {
+        self.event_handler.is_ended()
+    }
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<()> {
+        self.event_handler.poll_ready(cx)
+    }
+}
+
+struct EventHandlerLogicHandler<H>
+where
+    H: Handler,
+{
+    handler: H,
+    context: HandlerContext,
+    is_started: bool,
+    /// Slot for the handler task, allocated once and reused for every run.
+    handler_task: Pin<Box<Fuse<H::Future>>>,
+}
+
+impl<H> EventHandlerLogic<H::Error> for EventHandlerLogicHandler<H>
+where
+    H: Handler,
+{
+    fn handler_task(&mut self) -> HandlerTask<H::Error> {
+        if self.is_started {
+            self.context.advance();
+        } else {
+            self.is_started = true;
+            self.handler.on_start();
+        }
+
+        self.handler_task
+            .set(self.handler.run(&self.context).fuse());
+        HandlerTask::Pending
+    }
+
+    fn poll_handler_task(&mut self, cx: &mut Context<'_>) -> Poll<EventHandlerResult<H::Error>> {
+        self.handler_task.as_mut().poll(cx)
+    }
+
+    #[cfg(feature = "watchdog")]
+    fn reset(&mut self) {
+        self.handler_task.set(Fuse::terminated());
+    }
+
+    fn on_exit(&mut self) {
+        if self.is_started {
+            self.handler.on_exit();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::fmt;
+    #[cfg(feature = "watchdog")]
+    use std::time::Duration;
+    use std::{
+        error::Error,
+        sync::atomic::{AtomicUsize, Ordering},
+    };
+
+    use crossbeam_channel::SendError;
+    use futures::stream;
+
+    use crate::{ErrorPolicy, EventHandler, EventHandlingOutcome};
+    #[cfg(feature = "watchdog")]
+    use crate::{Watchdog, WatchdogPolicy};
+
+    #[test]
+    fn name_defaults_to_closure_type_name() {
+        let event_handler = continuer();
+
+        assert!(
+            event_handler.name().contains("continuer::{{closure}}"),
+            "name: {}",
+            event_handler.name()
+        );
+    }
+
+    #[test]
+    fn with_name_sets_name() {
+        let event_handler = continuer().with_name("physics");
+
+        assert_eq!("physics", event_handler.name());
+    }
+
+    #[test]
+    fn new_sync_runs_logic_without_future() {
+        let mut count = 0;
+        let mut event_handler = EventHandler::<SendError<()>>::new_sync(move || {
+            count += 1;
+            if count < 2 {
+                Ok(EventHandlingOutcome::Continue)
+            } else {
+                Ok(EventHandlingOutcome::Exit)
+            }
+        });
+
+        assert_eq!(
+            Ok(EventHandlingOutcome::Continue),
+            smol::run(event_handler.run())
+        );
+        assert_eq!(
+            Ok(EventHandlingOutcome::Exit),
+            smol::run(event_handler.run())
+        );
+    }
+
+    #[test]
+    fn from_stream_runs_logic_once_per_item() {
+        let mut results = Vec::new();
+        let mut event_handler = EventHandler::<SendError<()>>::from_stream(
+            stream::iter(vec![1, 2]),
+            |item| async move {
+                if item < 2 {
+                    Ok(EventHandlingOutcome::Continue)
+                } else {
+                    Ok(EventHandlingOutcome::Exit)
+                }
+            },
+        );
+
+        for _ in 0..3 {
+            results.push(smol::run(event_handler.run()));
+        }
+
+        assert_eq!(
+            vec![
+                Ok(EventHandlingOutcome::Continue),
+                Ok(EventHandlingOutcome::Exit),
+                Ok(EventHandlingOutcome::Continue),
+            ],
+            results
+        );
+    }
+
+    #[test]
+    fn run_returns_error_when_error_policy_is_exit() {
+        let mut event_handler = errorer();
+
+        assert!(matches!(event_handler.error_policy(), ErrorPolicy::Exit));
+        assert_eq!(Err(SendError(())), smol::run(event_handler.run()));
+    }
+
+    #[test]
+    fn run_continues_and_reports_error_when_error_policy_is_skip() {
+        static REPORT_COUNT: AtomicUsize = AtomicUsize::new(0);
+        fn reporter(name: &str, error: &dyn Error) {
+            assert_eq!("telemetry", name);
+            assert_eq!("sending on a disconnected channel", error.to_string());
+            REPORT_COUNT.fetch_add(1, Ordering::SeqCst);
+        }
+
+        let mut event_handler = errorer()
+            .with_name("telemetry")
+            .with_error_policy(ErrorPolicy::Skip(reporter));
+
+        assert_eq!(
+            Ok(EventHandlingOutcome::Continue),
+            smol::run(event_handler.run())
+        );
+        assert_eq!(1, REPORT_COUNT.load(Ordering::SeqCst));
+    }
+
+    #[test]
+    fn err_into_run_continues_and_reports_mapped_error_when_error_policy_is_skip() {
+        static REPORT_COUNT: AtomicUsize = AtomicUsize::new(0);
+        fn reporter(name: &str, error: &dyn Error) {
+            assert_eq!("telemetry", name);
+            assert_eq!(
+                "mapped: sending on a disconnected channel",
+                error.to_string()
+            );
+            REPORT_COUNT.fetch_add(1, Ordering::SeqCst);
+        }
+
+        let mut event_handler = errorer()
+            .with_name("telemetry")
+            .err_into::<MappedError>()
+            .with_error_policy(ErrorPolicy::Skip(reporter));
+
+        assert_eq!(
+            Ok(EventHandlingOutcome::Continue),
+            smol::run(event_handler.run())
+        );
+        assert_eq!(1, REPORT_COUNT.load(Ordering::SeqCst));
+    }
+
+    #[test]
+    fn err_into_run_applies_only_mapped_error_policy() {
+        fn reporter(_name: &str, _error: &dyn Error) {
+            panic!("Error policy of the unmapped event handler was applied.");
+        }
+
+        let mut event_handler = errorer()
+            .with_error_policy(ErrorPolicy::Skip(reporter))
+            .err_into::<MappedError>()
+            .with_error_policy(ErrorPolicy::Exit);
+
+        assert_eq!(
+            Err(MappedError(SendError(()))),
+            smol::run(event_handler.run())
+        );
+    }
+
+    #[cfg(feature = "watchdog")]
+    #[test]
+    fn err_into_with_watchdog_run_continues_and_reports_mapped_error_when_error_policy_is_skip() {
+        static REPORT_COUNT: AtomicUsize = AtomicUsize::new(0);
+        fn reporter(name: &str, error: &dyn Error) {
+            assert_eq!("hung", name);
+            assert_eq!(
+                "mapped: sending on a disconnected channel",
+                error.to_string()
+            );
+            REPORT_COUNT.fetch_add(1, Ordering::SeqCst);
+        }
+
+        let mut event_handler = EventHandler::<SendError<()>>::new(|| async move {
+            futures::future::pending::<()>().await;
+            Ok(EventHandlingOutcome::Continue)
+        })
+        .with_name("hung")
+        .with_watchdog(Watchdog::new(
+            Duration::from_millis(5),
+            WatchdogPolicy::CancelAndError(|_| SendError(())),
+            |_| {},
+        ))
+        .err_into::<MappedError>()
+        .with_error_policy(ErrorPolicy::Skip(reporter));
+
+        assert_eq!(
+            Ok(EventHandlingOutcome::Continue),
+            smol::run(event_handler.run())
+        );
+        assert_eq!(1, REPORT_COUNT.load(Ordering::SeqCst));
+    }
+
+    fn continuer() -> EventHandler<SendError<()>> {
+        EventHandler::<SendError<()>>::new(|| async move { Ok(EventHandlingOutcome::Continue) })
+    }
+
+    fn errorer() -> EventHandler<SendError<()>> {
+        EventHandler::<SendError<()>>::new_sync(|| Err(SendError(())))
+    }
+
+    /// Error that event handler errors are mapped into.
+    #[derive(Debug, PartialEq)]
+    struct MappedError(SendError<()>);
+
+    impl fmt::Display for MappedError {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            write!(f, "mapped: {}", self.0)
+        }
+    }
+
+    impl Error for MappedError {}
+
+    impl From<SendError<()>> for MappedError {
+        fn from(error: SendError<()>) -> Self {
+            MappedError(error)
+        }
     }
 }
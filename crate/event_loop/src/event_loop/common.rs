@@ -1,4 +1,7 @@
-pub(crate) use self::{event_handlers_ext::EventHandlersExt, rate_limiter::RateLimiter};
+pub(crate) use self::{
+    event_handlers_ext::EventHandlersExt, rate_limiter::RateLimiter, scheduler::Scheduler,
+};
 
 mod event_handlers_ext;
 mod rate_limiter;
+mod scheduler;
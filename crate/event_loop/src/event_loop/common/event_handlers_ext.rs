@@ -1,7 +1,17 @@
-use crate::{event_loop::common::RateLimiter, EventHandler};
+#[cfg(feature = "window")]
+use std::num::NonZeroU32;
+
+use crate::{event_loop::common::RateLimiter, EventHandler, RateLimit};
 
 pub(crate) trait EventHandlersExt<E> {
+    #[cfg(not(feature = "window"))]
     fn rate_limiters(&self) -> Vec<Option<RateLimiter>>;
+
+    #[cfg(feature = "window")]
+    fn frame_paced_rate_limiters(
+        &self,
+        display_refresh_rate: Option<NonZeroU32>,
+    ) -> Vec<Option<RateLimiter>>;
 }
 
 impl<T, E> EventHandlersExt<E> for T
@@ -10,26 +20,38 @@ where
 {
     /// Returns `RateLimiter`s constructed from each event handler's
     /// [`RateLimit`].
+    #[cfg(not(feature = "window"))]
     fn rate_limiters(&self) -> Vec<Option<RateLimiter>> {
         AsRef::<[EventHandler<E>]>::as_ref(self)
             .iter()
-            .map(|event_handler| {
-                let quota = event_handler
-                    .rate_limit
-                    .and_then(|rate_limit| rate_limit.quota());
-
-                // On WASM, if you have a non-rate-limited event handler, the browser will
-                // freeze when running single threaded.
-                #[cfg(target_arch = "wasm32")]
-                #[cfg_attr(tarpaulin, skip)]
-                let quota = {
-                    use governor::Quota;
-                    use std::time::Duration;
-                    quota.or_else(|| Quota::with_period(Duration::from_nanos(1)))
-                };
+            .map(rate_limiter)
+            .collect::<Vec<_>>()
+    }
 
-                quota.map(RateLimiter::direct)
+    /// Returns `RateLimiter`s constructed from each event handler's
+    /// [`FramePacing`], or [`RateLimit`] if it is not frame paced.
+    ///
+    /// [`FramePacing`]: crate::FramePacing
+    #[cfg(feature = "window")]
+    fn frame_paced_rate_limiters(
+        &self,
+        display_refresh_rate: Option<NonZeroU32>,
+    ) -> Vec<Option<RateLimiter>> {
+        AsRef::<[EventHandler<E>]>::as_ref(self)
+            .iter()
+            .map(|event_handler| match event_handler.frame_pacing {
+                Some(frame_pacing) => Some(RateLimiter::frame_paced(
+                    frame_pacing.frame_interval(display_refresh_rate),
+                )),
+                None => rate_limiter(event_handler),
             })
             .collect::<Vec<_>>()
     }
 }
+
+fn rate_limiter<E>(event_handler: &EventHandler<E>) -> Option<RateLimiter> {
+    let rate_limit = event_handler.rate_limit;
+    let quota = rate_limit.and_then(RateLimit::quota);
+
+    quota.map(|quota| RateLimiter::new(quota, rate_limit))
+}
@@ -1,6 +1,353 @@
-use governor::{
-    clock::DefaultClock,
-    state::{direct::NotKeyed, InMemoryState},
+use std::{
+    collections::hash_map::RandomState,
+    hash::{BuildHasher, Hasher},
+    time::Duration,
 };
 
-pub(crate) type RateLimiter = governor::RateLimiter<NotKeyed, InMemoryState, DefaultClock>;
+use instant::Instant;
+
+use crate::{Quota, RateLimit};
+
+/// Tracks when an event handler may next run, according to its [`RateLimit`].
+///
+/// The quota is enforced using the generic cell rate algorithm: each
+/// invocation pushes the theoretical arrival time forward by one replenish
+/// interval, and an invocation is permitted while the theoretical arrival time
+/// is within the burst allowance of the current time.
+#[derive(Debug)]
+pub(crate) struct RateLimiter {
+    /// Duration to replenish one invocation.
+    replenish_interval: Duration,
+    /// How far ahead of the current time the theoretical arrival time may be.
+    burst_allowance: Duration,
+    /// Theoretical arrival time of the next invocation.
+    tat: Option<Instant>,
+    /// Minimum duration between two invocations.
+    min_spacing: Duration,
+    /// Maximum random delay added before each invocation.
+    jitter: Duration,
+    /// When the event handler last ran.
+    last_run: Option<Instant>,
+    /// When the event handler may run, after a jitter delay.
+    ready_at: Option<Instant>,
+    /// State of the pseudo random number generator for jitter.
+    rng_state: u64,
+    /// Frame boundaries to align invocations to, if frame paced.
+    #[cfg(feature = "window")]
+    frame_grid: Option<FrameGrid>,
+}
+
+impl RateLimiter {
+    /// Returns a `RateLimiter` for the given `quota` and `rate_limit` controls.
+    pub fn new(quota: Quota, rate_limit: Option<RateLimit>) -> Self {
+        let replenish_interval = quota.replenish_interval();
+        let burst_allowance = quota.burst_allowance();
+        let min_spacing = rate_limit
+            .and_then(RateLimit::min_spacing)
+            .unwrap_or_default();
+        let jitter = rate_limit.and_then(RateLimit::jitter).unwrap_or_default();
+
+        // Seed from `RandomState`, which differs per instance, so that event handlers
+        // with the same rate limit receive different jitter.
+        let rng_state = RandomState::new().build_hasher().finish() | 1;
+
+        Self {
+            replenish_interval,
+            burst_allowance,
+            tat: None,
+            min_spacing,
+            jitter,
+            last_run: None,
+            ready_at: None,
+            rng_state,
+            #[cfg(feature = "window")]
+            frame_grid: None,
+        }
+    }
+
+    /// Returns a `RateLimiter` that aligns invocations to frame boundaries.
+    ///
+    /// # Parameters
+    ///
+    /// * `frame_interval`: Duration between frame boundaries.
+    #[cfg(feature = "window")]
+    pub fn frame_paced(frame_interval: Duration) -> Self {
+        Self {
+            replenish_interval: frame_interval,
+            burst_allowance: Duration::from_nanos(0),
+            tat: None,
+            min_spacing: Duration::from_nanos(0),
+            jitter: Duration::from_nanos(0),
+            last_run: None,
+            ready_at: None,
+            rng_state: 1,
+            frame_grid: Some(FrameGrid::new(frame_interval)),
+        }
+    }
+
+    /// Returns whether this rate limiter aligns invocations to frame
+    /// boundaries.
+    #[cfg(feature = "window")]
+    pub fn is_frame_paced(&self) -> bool {
+        self.frame_grid.is_some()
+    }
+
+    /// Returns `Ok` if the event handler may run at `now`, otherwise the
+    /// instant to check again.
+    ///
+    /// When `Ok` is returned, the invocation is recorded against the limit. The
+    /// returned instant is always later than `now`.
+    pub fn check(&mut self, now: Instant) -> Result<(), Instant> {
+        #[cfg(feature = "window")]
+        {
+            if let Some(frame_grid) = self.frame_grid.as_mut() {
+                return frame_grid.check(now);
+            }
+        }
+
+        if let Some(ready_at) = self.ready_at {
+            return if now >= ready_at {
+                self.ready_at = None;
+                self.last_run = Some(now);
+                Ok(())
+            } else {
+                Err(ready_at)
+            };
+        }
+
+        if let Some(spaced_at) = self.spaced_at() {
+            if now < spaced_at {
+                return Err(spaced_at);
+            }
+        }
+
+        if let Some(tat) = self.tat {
+            if now + self.burst_allowance < tat {
+                return Err(tat - self.burst_allowance);
+            }
+        }
+
+        let tat = self.tat.map_or(now, |tat| std::cmp::max(tat, now));
+        self.tat = Some(tat + self.replenish_interval);
+
+        let jitter = self.jitter_sample();
+        if jitter == Duration::from_nanos(0) {
+            self.last_run = Some(now);
+            Ok(())
+        } else {
+            let ready_at = now + jitter;
+            self.ready_at = Some(ready_at);
+            Err(ready_at)
+        }
+    }
+
+    /// Returns the earliest instant the event handler may run, without
+    /// recording an invocation.
+    ///
+    /// # Parameters
+    ///
+    /// * `now`: Returned if the event handler may run immediately.
+    pub fn next_due(&self, now: Instant) -> Instant {
+        #[cfg(feature = "window")]
+        {
+            if let Some(frame_grid) = self.frame_grid.as_ref() {
+                return frame_grid.next_due(now);
+            }
+        }
+
+        if let Some(ready_at) = self.ready_at {
+            return ready_at;
+        }
+
+        let gcra_at = self
+            .tat
+            .map(|tat| tat.checked_sub(self.burst_allowance).unwrap_or(now));
+
+        [gcra_at, self.spaced_at()]
+            .iter()
+            .flatten()
+            .fold(now, |due, instant| std::cmp::max(due, *instant))
+    }
+
+    /// Returns the earliest instant permitted by the minimum spacing.
+    fn spaced_at(&self) -> Option<Instant> {
+        self.last_run.map(|last_run| last_run + self.min_spacing)
+    }
+
+    /// Returns a random duration between zero and the jitter.
+    fn jitter_sample(&mut self) -> Duration {
+        if self.jitter == Duration::from_nanos(0) {
+            return self.jitter;
+        }
+
+        // xorshift64
+        self.rng_state ^= self.rng_state << 13;
+        self.rng_state ^= self.rng_state >> 7;
+        self.rng_state ^= self.rng_state << 17;
+
+        let jitter_nanos = self.jitter.as_nanos() as u64;
+        Duration::from_nanos(self.rng_state % (jitter_nanos + 1))
+    }
+}
+
+/// Fixed grid of frame boundaries that invocations are aligned to.
+#[cfg(feature = "window")]
+#[derive(Debug)]
+struct FrameGrid {
+    /// Duration between frame boundaries.
+    interval: Duration,
+    /// How early an invocation may run before its frame boundary.
+    ///
+    /// On WASM, wake ups are driven by `requestAnimationFrame`, whose timing
+    /// varies slightly from the grid, so invocations may run up to half a frame
+    /// early. Natively the event loop spins until the boundary is reached.
+    slack: Duration,
+    /// First frame boundary.
+    epoch: Option<Instant>,
+    /// Next frame boundary to run at.
+    next_frame: Option<Instant>,
+}
+
+#[cfg(feature = "window")]
+impl FrameGrid {
+    fn new(interval: Duration) -> Self {
+        #[cfg(not(target_arch = "wasm32"))]
+        let slack = Duration::from_nanos(0);
+        #[cfg(target_arch = "wasm32")]
+        let slack = interval / 2;
+
+        Self {
+            interval,
+            slack,
+            epoch: None,
+            next_frame: None,
+        }
+    }
+
+    fn check(&mut self, now: Instant) -> Result<(), Instant> {
+        if let Some(next_frame) = self.next_frame {
+            if now + self.slack < next_frame {
+                return Err(next_frame - self.slack);
+            }
+        }
+
+        // Skip to the first frame boundary after `now`, so that missed frames are
+        // dropped instead of run back to back.
+        let epoch = *self.epoch.get_or_insert(now);
+        let interval_nanos = std::cmp::max(self.interval.as_nanos(), 1);
+        let frames_elapsed = (now + self.slack - epoch).as_nanos() / interval_nanos;
+        let next_frame_offset = (frames_elapsed + 1) * interval_nanos;
+        self.next_frame = Some(epoch + Duration::from_nanos(next_frame_offset as u64));
+
+        Ok(())
+    }
+
+    fn next_due(&self, now: Instant) -> Instant {
+        self.next_frame
+            .and_then(|next_frame| next_frame.checked_sub(self.slack))
+            .map_or(now, |due| std::cmp::max(due, now))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{num::NonZeroU32, time::Duration};
+
+    use instant::Instant;
+
+    use super::RateLimiter;
+    use crate::{Error, Quota, RateLimit};
+
+    #[test]
+    fn check_respects_burst_size() -> Result<(), Error> {
+        let rate_limit = RateLimit::fps(1)?.with_burst(2)?;
+        let quota = rate_limit.quota().expect("Expected quota to exist.");
+        let mut rate_limiter = RateLimiter::new(quota, Some(rate_limit));
+        let now = Instant::now();
+
+        assert_eq!(Ok(()), rate_limiter.check(now));
+        assert_eq!(Ok(()), rate_limiter.check(now));
+        assert_eq!(Err(now + Duration::from_secs(1)), rate_limiter.check(now));
+        Ok(())
+    }
+
+    #[test]
+    fn check_replenishes_after_interval() {
+        let quota = Quota::with_period(Duration::from_millis(10)).unwrap();
+        let mut rate_limiter = RateLimiter::new(quota, None);
+        let now = Instant::now();
+
+        assert_eq!(Ok(()), rate_limiter.check(now));
+        assert_eq!(
+            Err(now + Duration::from_millis(10)),
+            rate_limiter.check(now + Duration::from_millis(5))
+        );
+        assert_eq!(Ok(()), rate_limiter.check(now + Duration::from_millis(10)));
+    }
+
+    #[test]
+    fn check_respects_min_spacing_during_burst() -> Result<(), Error> {
+        let rate_limit = RateLimit::fps(1)?
+            .with_burst(2)?
+            .with_min_spacing(Duration::from_millis(500))?;
+        let quota = rate_limit.quota().expect("Expected quota to exist.");
+        let mut rate_limiter = RateLimiter::new(quota, Some(rate_limit));
+        let now = Instant::now();
+
+        assert_eq!(Ok(()), rate_limiter.check(now));
+        assert_eq!(
+            Err(now + Duration::from_millis(500)),
+            rate_limiter.check(now)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn next_due_does_not_record_invocation() {
+        let quota = Quota::with_period(Duration::from_millis(10)).unwrap();
+        let mut rate_limiter = RateLimiter::new(quota, None);
+        let now = Instant::now();
+
+        assert_eq!(now, rate_limiter.next_due(now));
+        assert_eq!(Ok(()), rate_limiter.check(now));
+        assert_eq!(now + Duration::from_millis(10), rate_limiter.next_due(now));
+        assert_eq!(now + Duration::from_millis(10), rate_limiter.next_due(now));
+    }
+
+    #[cfg(all(feature = "window", not(target_arch = "wasm32")))]
+    #[test]
+    fn frame_paced_aligns_to_frame_boundaries() {
+        let mut rate_limiter = RateLimiter::frame_paced(Duration::from_millis(10));
+        let now = Instant::now();
+
+        assert_eq!(Ok(()), rate_limiter.check(now));
+        assert_eq!(
+            Err(now + Duration::from_millis(10)),
+            rate_limiter.check(now + Duration::from_millis(3))
+        );
+        assert_eq!(Ok(()), rate_limiter.check(now + Duration::from_millis(12)));
+        assert_eq!(
+            now + Duration::from_millis(20),
+            rate_limiter.next_due(now + Duration::from_millis(12))
+        );
+
+        // Missed frames are skipped.
+        assert_eq!(Ok(()), rate_limiter.check(now + Duration::from_millis(45)));
+        assert_eq!(
+            now + Duration::from_millis(50),
+            rate_limiter.next_due(now + Duration::from_millis(45))
+        );
+    }
+
+    #[test]
+    fn jitter_sample_is_within_jitter() -> Result<(), Error> {
+        let jitter = Duration::from_millis(3);
+        let rate_limit = RateLimit::interval(Duration::from_millis(10)).with_jitter(jitter)?;
+        let quota =
+            Quota::per_second(NonZeroU32::new(100).unwrap()).expect("Interval is non-zero.");
+        let mut rate_limiter = RateLimiter::new(quota, Some(rate_limit));
+
+        (0..100).for_each(|_| assert!(rate_limiter.jitter_sample() <= jitter));
+        Ok(())
+    }
+}
@@ -0,0 +1,285 @@
+use std::{cmp::Reverse, collections::BinaryHeap};
+
+use instant::Instant;
+
+use crate::event_loop::common::RateLimiter;
+
+/// Priority queue of when each event handler is next due to run.
+///
+/// Event handlers are identified by their index in the event loop.
+#[derive(Debug)]
+pub(crate) struct Scheduler {
+    /// Rate limiter for each event handler, `None` if unlimited.
+    rate_limiters: Vec<Option<RateLimiter>>,
+    /// Next due instant of each event handler that is not currently running.
+    deadlines: BinaryHeap<Reverse<(Instant, usize)>>,
+    /// Event driven event handlers that are waiting for an event.
+    parked: Vec<usize>,
+    /// Event handlers that were due while disabled.
+    disabled: Vec<usize>,
+}
+
+impl Scheduler {
+    /// Returns a `Scheduler` with every event handler due immediately.
+    ///
+    /// # Parameters
+    ///
+    /// * `rate_limiters`: Rate limiter for each event handler.
+    pub fn new(rate_limiters: Vec<Option<RateLimiter>>) -> Self {
+        let now = Instant::now();
+        let deadlines = (0..rate_limiters.len())
+            .map(|index| Reverse((now, index)))
+            .collect::<BinaryHeap<_>>();
+
+        Self {
+            rate_limiters,
+            deadlines,
+            parked: Vec::new(),
+            disabled: Vec::new(),
+        }
+    }
+
+    /// Moves the indices of event handlers that may run at `now` into `due`,
+    /// in event handler order.
+    ///
+    /// Event handlers that are disabled are set aside without consuming their
+    /// rate limit, and are due once they are enabled.
+    ///
+    /// Each returned index must be passed to [`Scheduler::reschedule`] after
+    /// the event handler has run.
+    ///
+    /// # Parameters
+    ///
+    /// * `now`: The current instant.
+    /// * `due`: Vector to move the due indices into.
+    /// * `is_enabled`: Returns whether the event handler at an index is
+    ///   enabled.
+    pub fn take_due<F>(&mut self, now: Instant, due: &mut Vec<usize>, mut is_enabled: F)
+    where
+        F: FnMut(usize) -> bool,
+    {
+        self.enable(now, &mut is_enabled);
+
+        while let Some(Reverse((deadline, index))) = self.deadlines.peek().copied() {
+            if deadline > now {
+                break;
+            }
+            self.deadlines.pop();
+
+            if !is_enabled(index) {
+                self.disabled.push(index);
+                continue;
+            }
+
+            match self.rate_limiters[index]
+                .as_mut()
+                .map(|rate_limiter| rate_limiter.check(now))
+            {
+                Some(Err(deadline)) => self.deadlines.push(Reverse((deadline, index))),
+                Some(Ok(())) | None => due.push(index),
+            }
+        }
+
+        due.sort_unstable();
+    }
+
+    /// Schedules the disabled event handlers that have been enabled, due at
+    /// `now`.
+    ///
+    /// # Parameters
+    ///
+    /// * `now`: The current instant.
+    /// * `is_enabled`: Returns whether the event handler at an index is
+    ///   enabled.
+    pub fn enable<F>(&mut self, now: Instant, mut is_enabled: F)
+    where
+        F: FnMut(usize) -> bool,
+    {
+        let mut disabled_index = 0;
+        while let Some(index) = self.disabled.get(disabled_index).copied() {
+            if is_enabled(index) {
+                self.disabled.swap_remove(disabled_index);
+                self.deadlines.push(Reverse((now, index)));
+            } else {
+                disabled_index += 1;
+            }
+        }
+    }
+
+    /// Schedules the event handler at `index` for its next run.
+    ///
+    /// # Parameters
+    ///
+    /// * `index`: Index of the event handler that has run.
+    /// * `now`: When the event handler finished running.
+    pub fn reschedule(&mut self, index: usize, now: Instant) {
+        let deadline = self.rate_limiters[index]
+            .as_ref()
+            .map_or(now, |rate_limiter| rate_limiter.next_due(now));
+
+        self.deadlines.push(Reverse((deadline, index)));
+    }
+
+    /// Parks the event handler at `index` until it has an event.
+    ///
+    /// This is used instead of [`Scheduler::reschedule`] for event driven
+    /// event handlers.
+    ///
+    /// # Parameters
+    ///
+    /// * `index`: Index of the event handler that has run.
+    pub fn park(&mut self, index: usize) {
+        self.parked.push(index);
+    }
+
+    /// Schedules the parked event handlers that are ready to run.
+    ///
+    /// # Parameters
+    ///
+    /// * `now`: The current instant.
+    /// * `is_ready`: Returns whether the event handler at an index has an
+    ///   event.
+    pub fn unpark<F>(&mut self, now: Instant, mut is_ready: F)
+    where
+        F: FnMut(usize) -> bool,
+    {
+        let mut parked_index = 0;
+        while let Some(index) = self.parked.get(parked_index).copied() {
+            if is_ready(index) {
+                self.parked.swap_remove(parked_index);
+                self.reschedule(index, now);
+            } else {
+                parked_index += 1;
+            }
+        }
+    }
+
+    /// Returns the indices of event handlers that are waiting for an event.
+    #[cfg(any(not(feature = "window"), test))]
+    pub fn parked(&self) -> &[usize] {
+        &self.parked
+    }
+
+    /// Returns whether any event handler is parked or disabled, and so is not
+    /// scheduled until it is woken or enabled.
+    pub fn has_unscheduled(&self) -> bool {
+        !self.parked.is_empty() || !self.disabled.is_empty()
+    }
+
+    /// Returns whether no event handler is scheduled, parked, or disabled, as
+    /// every event handler has ended.
+    #[cfg(feature = "window")]
+    pub fn is_empty(&self) -> bool {
+        self.deadlines.is_empty() && !self.has_unscheduled()
+    }
+
+    /// Returns the earliest instant any event handler is due, if any.
+    pub fn next_deadline(&self) -> Option<Instant> {
+        self.deadlines
+            .peek()
+            .map(|Reverse((deadline, _index))| *deadline)
+    }
+
+    /// Returns the earliest instant any event handler is due, if that event
+    /// handler is frame paced.
+    #[cfg(feature = "window")]
+    pub fn next_frame_deadline(&self) -> Option<Instant> {
+        self.deadlines
+            .peek()
+            .filter(
+                |Reverse((_deadline, index))| match &self.rate_limiters[*index] {
+                    Some(rate_limiter) => rate_limiter.is_frame_paced(),
+                    None => false,
+                },
+            )
+            .map(|Reverse((deadline, _index))| *deadline)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use instant::Instant;
+
+    use super::Scheduler;
+    use crate::{event_loop::common::RateLimiter, Quota};
+
+    #[test]
+    fn take_due_returns_event_handlers_in_order() {
+        let mut scheduler = Scheduler::new(vec![None, None, None]);
+        let mut due = Vec::new();
+
+        scheduler.take_due(Instant::now(), &mut due, |_| true);
+
+        assert_eq!(vec![0, 1, 2], due);
+    }
+
+    #[test]
+    fn take_due_skips_rate_limited_event_handlers_until_deadline() {
+        let quota = Quota::with_period(Duration::from_millis(10)).unwrap();
+        let mut scheduler = Scheduler::new(vec![Some(RateLimiter::new(quota, None)), None]);
+        let mut due = Vec::new();
+        let now = Instant::now();
+
+        scheduler.take_due(now, &mut due, |_| true);
+        due.drain(..)
+            .for_each(|index| scheduler.reschedule(index, now));
+        scheduler.take_due(now + Duration::from_millis(5), &mut due, |_| true);
+
+        assert_eq!(vec![1], due);
+        assert_eq!(
+            Some(now + Duration::from_millis(10)),
+            scheduler.next_deadline()
+        );
+
+        due.clear();
+        scheduler.take_due(now + Duration::from_millis(10), &mut due, |_| true);
+
+        assert_eq!(vec![0], due);
+    }
+
+    #[test]
+    fn parked_event_handlers_are_not_due_until_unparked() {
+        let mut scheduler = Scheduler::new(vec![None, None]);
+        let mut due = Vec::new();
+        let now = Instant::now();
+
+        scheduler.take_due(now, &mut due, |_| true);
+        scheduler.park(0);
+        scheduler.park(1);
+        due.clear();
+
+        scheduler.unpark(now, |index| index == 1);
+        scheduler.take_due(now, &mut due, |_| true);
+
+        assert_eq!(vec![1], due);
+        assert_eq!(&[0], scheduler.parked());
+    }
+
+    #[test]
+    fn disabled_event_handlers_do_not_consume_rate_limit() {
+        let quota = Quota::with_period(Duration::from_millis(10)).unwrap();
+        let mut scheduler = Scheduler::new(vec![Some(RateLimiter::new(quota, None))]);
+        let mut due = Vec::new();
+        let now = Instant::now();
+
+        scheduler.take_due(now, &mut due, |_| false);
+
+        assert!(due.is_empty());
+        assert!(scheduler.has_unscheduled());
+        assert_eq!(None, scheduler.next_deadline());
+
+        scheduler.take_due(now, &mut due, |_| true);
+
+        assert_eq!(vec![0], due);
+        assert!(!scheduler.has_unscheduled());
+    }
+
+    #[test]
+    fn next_deadline_returns_none_when_no_event_handlers() {
+        let scheduler = Scheduler::new(Vec::new());
+
+        assert_eq!(None, scheduler.next_deadline());
+    }
+}
@@ -1,26 +1,68 @@
-use std::error::Error;
+use std::{
+    error::Error,
+    task::{Context, Poll},
+};
 
-use crate::EventHandler;
+use futures::{
+    channel::mpsc::{self, UnboundedReceiver, UnboundedSender},
+    future,
+    stream::StreamExt,
+};
 
+#[cfg(feature = "rate_limit")]
+use crate::IdleStrategy;
+#[cfg(feature = "config")]
+use crate::{ConfigError, EventLoopConfig};
+use crate::{
+    EventBus, EventHandler, EventLoopProxy, EventWriter, HandlerGroups, Introspection, State,
+    StateStack, StopHandle,
+};
+
+#[cfg(target_arch = "wasm32")]
+mod animation_frame;
 #[cfg(not(feature = "rate_limit"))]
 mod rate_limit_off;
 #[cfg(feature = "rate_limit")]
 mod rate_limit_on;
+mod yield_now;
 
 /// Receives events and runs an event handler function.
 ///
 /// # Type Parameters
 ///
 /// * `E`: Error type.
+/// * `UserEvent`: Custom user event type, defaults to `()`.
 #[derive(Debug)]
-pub struct EventLoop<E> {
+pub struct EventLoop<E, UserEvent = ()>
+where
+    UserEvent: 'static,
+{
     /// `EventHandler`s to run during event loop execution.
     event_handlers: Vec<EventHandler<E>>,
+    /// What to do when no event handler is due.
+    #[cfg(feature = "rate_limit")]
+    idle_strategy: IdleStrategy,
+    /// Requests the event loop to exit.
+    stop_handle: StopHandle,
+    /// Channels between event handlers.
+    event_bus: EventBus,
+    /// Groups of event handlers that are disabled.
+    handler_groups: HandlerGroups,
+    /// Stack of game states, whose event handlers run while current.
+    state_stack: StateStack,
+    /// Lists the event handlers while the event loop is running.
+    introspection: Introspection,
+    /// Sends user events, cloned into each `EventLoopProxy`.
+    user_event_tx: UnboundedSender<UserEvent>,
+    /// Receives user events from `EventLoopProxy`s.
+    user_event_rx: UnboundedReceiver<UserEvent>,
+    /// Emits received user events to the event bus.
+    user_event_writer: EventWriter<UserEvent>,
 }
 
-impl<E> EventLoop<E>
+impl<E> EventLoop<E, ()>
 where
-    E: Error,
+    E: Error + 'static,
 {
     /// Returns a new `EventLoop`.
     ///
@@ -28,106 +70,240 @@ where
     ///
     /// * `event_handlers`: The logic to run for each event loop execution.
     pub fn new(event_handlers: Vec<EventHandler<E>>) -> Self {
-        Self { event_handlers }
+        Self::new_with_event(event_handlers)
     }
-}
-
-#[cfg(test)]
-mod tests {
-    #[cfg(feature = "rate_limit")]
-    use std::time::Duration;
 
-    use crossbeam_channel::{SendError, Sender};
+    /// Returns a new `EventLoop` that receives custom user events.
+    ///
+    /// # Parameters
+    ///
+    /// * `event_handlers`: The logic to run for each event loop execution.
+    pub fn new_with_event<UserEvent>(
+        event_handlers: Vec<EventHandler<E>>,
+    ) -> EventLoop<E, UserEvent> {
+        let event_bus = EventBus::new();
+        let (user_event_tx, user_event_rx) = mpsc::unbounded();
+        let user_event_writer = event_bus.writer::<UserEvent>();
+        let handler_groups = HandlerGroups::new();
+        let state_stack = StateStack::new(handler_groups.clone());
+        let introspection = Introspection::new(handler_groups.clone());
+        introspection.register(&event_handlers);
 
-    #[cfg(feature = "rate_limit")]
-    use crate::RateLimit;
-    use crate::{EventHandler, EventHandlingOutcome, EventLoop};
+        EventLoop::<E, UserEvent> {
+            event_handlers,
+            #[cfg(feature = "rate_limit")]
+            idle_strategy: IdleStrategy::default(),
+            stop_handle: StopHandle::new(),
+            event_bus,
+            handler_groups,
+            state_stack,
+            introspection,
+            user_event_tx,
+            user_event_rx,
+            user_event_writer,
+        }
+    }
+}
 
-    #[test]
-    fn run_runs_event_handlers_until_exit_is_signalled() -> Result<(), SendError<()>> {
-        let (tx, rx) = crossbeam_channel::bounded(10);
-        let event_handler_send = sender(tx);
-        let event_handler_countdown = countdown(3);
+impl<E, UserEvent> EventLoop<E, UserEvent>
+where
+    E: Error,
+    UserEvent: 'static,
+{
+    /// Adds an event handler to run after the existing event handlers.
+    ///
+    /// This allows event handlers to be created after obtaining handles from
+    /// the event loop, such as its `EventBus`.
+    ///
+    /// # Parameters
+    ///
+    /// * `event_handler`: The event handler to add.
+    pub fn with_event_handler(mut self, event_handler: EventHandler<E>) -> Self
+    where
+        E: 'static,
+    {
+        self.event_handlers.push(event_handler);
+        self.introspection.register(&self.event_handlers);
+        self
+    }
 
-        let event_loop = EventLoop::new(vec![event_handler_send, event_handler_countdown]);
+    /// Adds a state, whose event handlers run while it is the current state.
+    ///
+    /// The state's event handlers are added to the group named after the
+    /// state. The state is entered by pushing it onto the `StateStack`.
+    ///
+    /// # Parameters
+    ///
+    /// * `state`: The state to add.
+    pub fn with_state(mut self, state: State<E>) -> Self
+    where
+        E: 'static,
+    {
+        self.state_stack.add_state(state, &mut self.event_handlers);
+        self.introspection.register(&self.event_handlers);
+        self
+    }
 
-        smol::run(event_loop.run())?;
+    /// Overrides event handler settings with those declared in a
+    /// configuration, keyed by event handler name.
+    ///
+    /// This should be called after every event handler and state is added, as
+    /// event handlers added afterwards are not configured.
+    ///
+    /// # Parameters
+    ///
+    /// * `config`: The configuration, typically from `EventLoopConfig::load`.
+    /// * `error_reporter`: Function to report errors of event handlers that
+    ///   the configuration sets to skip them, e.g. to a logger.
+    #[cfg(feature = "config")]
+    #[cfg_attr(feature = "docs", doc(cfg(config)))]
+    pub fn with_config(
+        mut self,
+        config: &EventLoopConfig,
+        error_reporter: fn(&str, &dyn Error),
+    ) -> Result<Self, ConfigError>
+    where
+        E: 'static,
+    {
+        config.apply(&mut self.event_handlers, error_reporter)?;
+        self.introspection.register(&self.event_handlers);
+        Ok(self)
+    }
 
-        let count = rx.try_iter().collect::<Vec<()>>().len();
-        assert_eq!(3, count);
+    /// Returns a handle to request the event loop to exit.
+    ///
+    /// When the event loop is stopped, `run` returns `Ok(())`, and the reason
+    /// may be read from the `StopHandle`.
+    pub fn stop_handle(&self) -> StopHandle {
+        self.stop_handle.clone()
+    }
 
-        Ok(())
+    /// Returns the event bus, which event handlers use to send events to each
+    /// other.
+    pub fn event_bus(&self) -> EventBus {
+        self.event_bus.clone()
     }
 
-    #[test]
-    fn run_returns_on_first_error() -> Result<(), SendError<()>> {
-        let (tx, _rx) = crossbeam_channel::bounded(10);
-        let event_handler_send = sender(tx);
-        let event_handler_countdown = countdown(3);
+    /// Returns the handle to enable and disable groups of event handlers.
+    pub fn handler_groups(&self) -> HandlerGroups {
+        self.handler_groups.clone()
+    }
 
-        let event_loop =
-            EventLoop::new(vec![event_handler_send, errorer(), event_handler_countdown]);
+    /// Returns the stack of game states, which event handlers use to
+    /// transition between states.
+    pub fn state_stack(&self) -> StateStack {
+        self.state_stack.clone()
+    }
 
-        assert_eq!(SendError(()), smol::run(event_loop.run()).unwrap_err());
+    /// Returns the handle to list the event handlers and their status, which
+    /// may be sent to other threads.
+    pub fn introspection(&self) -> Introspection {
+        self.introspection.clone()
+    }
 
-        Ok(())
+    /// Returns a proxy to send user events to this event loop from any thread.
+    ///
+    /// User events are emitted to the event bus at the start of the next tick,
+    /// and read by event handlers through an `EventReader<UserEvent>`.
+    pub fn create_proxy(&self) -> EventLoopProxy<UserEvent> {
+        EventLoopProxy::new(self.user_event_tx.clone())
     }
 
+    /// Sets what the event loop does when no event handler is due.
+    ///
+    /// Defaults to [`IdleStrategy::ParkUntilDeadline`].
+    ///
+    /// # Parameters
+    ///
+    /// * `idle_strategy`: What to do when no event handler is due.
     #[cfg(feature = "rate_limit")]
-    #[test]
-    fn event_handlers_are_rate_limited_independently() -> Result<(), SendError<()>> {
-        let (tx0, rx0) = crossbeam_channel::unbounded();
-        let event_handler_send_0 = sender(tx0);
-
-        let (tx1, rx1) = crossbeam_channel::bounded(10);
-        let event_handler_send_1 =
-            sender(tx1).with_rate_limit(RateLimit::interval(Duration::from_millis(2)));
-        let event_handler_countdown =
-            countdown(3).with_rate_limit(RateLimit::interval(Duration::from_millis(3)));
-
-        let event_loop = EventLoop::new(vec![
-            event_handler_countdown,
-            event_handler_send_1,
-            event_handler_send_0,
-        ]);
-
-        smol::run(event_loop.run())?;
+    #[cfg_attr(feature = "docs", doc(cfg(rate_limit)))]
+    pub fn with_idle_strategy(mut self, idle_strategy: IdleStrategy) -> Self {
+        self.idle_strategy = idle_strategy;
+        self
+    }
+}
 
-        let count_0 = rx0.try_iter().collect::<Vec<()>>().len();
-        let count_1 = rx1.try_iter().collect::<Vec<()>>().len();
+cfg_spawner! {
+    impl<E, UserEvent> EventLoop<E, UserEvent>
+    where
+        UserEvent: 'static,
+    {
+        /// Returns a handle to spawn background tasks on the executor selected
+        /// by cargo features.
+        pub fn spawner(&self) -> crate::Spawner {
+            crate::Spawner::new()
+        }
+    }
+}
 
-        assert!(count_0 >= 8, "count_0: {}", count_0);
-        assert!(count_1 >= 2, "count_1: {}", count_1);
-        assert!(count_1 <= 6, "count_1: {}", count_1);
+impl<E, UserEvent> EventLoop<E, UserEvent>
+where
+    E: Error + 'static,
+    UserEvent: 'static,
+{
+    /// Emits user events received from `EventLoopProxy`s to the event bus.
+    ///
+    /// Returns `Ready` if any user event was received, otherwise registers the
+    /// waker.
+    fn poll_user_events(&mut self, cx: &mut Context<'_>) -> Poll<()> {
+        let mut is_received = false;
+        // The receiver does not end, as the event loop holds a sender.
+        while let Poll::Ready(Some(user_event)) = self.user_event_rx.poll_next_unpin(cx) {
+            self.user_event_writer.emit(user_event);
+            is_received = true;
+        }
 
-        Ok(())
+        if is_received {
+            Poll::Ready(())
+        } else {
+            Poll::Pending
+        }
     }
 
-    fn sender(tx: Sender<()>) -> EventHandler<SendError<()>> {
-        EventHandler::<SendError<()>>::new(move || {
-            let tx = tx.clone();
-            async move {
-                tx.send(())?;
-
-                Ok(EventHandlingOutcome::Continue)
-            }
+    /// Emits user events that have been received to the event bus.
+    async fn receive_user_events(&mut self) {
+        future::poll_fn(|cx| {
+            // Whether any were received does not matter, as the tick runs anyway.
+            let _ = self.poll_user_events(cx);
+            Poll::Ready(())
         })
+        .await
     }
 
-    fn countdown(mut count: u32) -> EventHandler<SendError<()>> {
-        EventHandler::<SendError<()>>::new(move || {
-            count -= 1;
-            async move {
-                if count > 0 {
-                    Ok(EventHandlingOutcome::Continue)
-                } else {
-                    Ok(EventHandlingOutcome::Exit)
-                }
+    /// Returns `Ready` when a stop is requested, when a user event is received,
+    /// when a handler group is enabled or disabled, or when any of the given
+    /// event handlers has an event, otherwise registers the waker.
+    ///
+    /// # Parameters
+    ///
+    /// * `cx`: Context of the idling task.
+    /// * `indices`: Indices of the event driven event handlers to poll.
+    fn poll_wake<I>(&mut self, cx: &mut Context<'_>, indices: I) -> Poll<()>
+    where
+        I: IntoIterator<Item = usize>,
+    {
+        if self.stop_handle.poll_stop_requested(cx).is_ready() {
+            return Poll::Ready(());
+        }
+
+        // Every event handler is polled, so that each one registers the waker. Event handlers
+        // that have ended are skipped, as they are always ready.
+        let mut is_ready = self.poll_user_events(cx).is_ready();
+        if self.handler_groups.poll_changed(cx).is_ready() {
+            is_ready = true;
+        }
+        for index in indices {
+            let event_handler = &mut self.event_handlers[index];
+            if !event_handler.is_ended() && event_handler.poll_ready(cx).is_ready() {
+                is_ready = true;
             }
-        })
-    }
+        }
 
-    fn errorer() -> EventHandler<SendError<()>> {
-        EventHandler::<SendError<()>>::new(|| async move { Err(SendError(())) })
+        if is_ready {
+            Poll::Ready(())
+        } else {
+            Poll::Pending
+        }
     }
 }
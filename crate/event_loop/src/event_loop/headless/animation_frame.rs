@@ -0,0 +1,131 @@
+use core::{
+    cell::{Cell, RefCell},
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll, Waker},
+};
+use std::rc::Rc;
+
+use wasm_bindgen::{closure::Closure, JsCast, JsValue};
+use web_sys::WorkerGlobalScope;
+
+/// Milliseconds to wait when animation frames are not available, which is
+/// about one frame at 60 Hz.
+const FRAME_TIMEOUT_MS: i32 = 16;
+
+/// Future that resolves on the next `requestAnimationFrame` callback.
+///
+/// This lets the event loop yield to the browser between ticks, instead of
+/// blocking its single thread.
+///
+/// Web workers have no `window`, so a `setTimeout` callback of about one frame
+/// is used instead. If neither can be scheduled, the future resolves
+/// immediately.
+pub(crate) struct AnimationFrame {
+    /// Whether the animation frame callback has run.
+    fired: Rc<Cell<bool>>,
+    /// Waker to notify when the animation frame callback runs.
+    waker: Rc<RefCell<Option<Waker>>>,
+    /// Callback registered with `requestAnimationFrame`, kept alive until the
+    /// future is dropped.
+    closure: Option<Closure<dyn FnMut()>>,
+    /// ID of the scheduled callback, used to cancel it when the future is
+    /// dropped before it fires.
+    handle: Option<Handle>,
+}
+
+/// ID of a callback scheduled by [`AnimationFrame`].
+#[derive(Clone, Copy)]
+enum Handle {
+    /// ID returned by `requestAnimationFrame`.
+    AnimationFrame(i32),
+    /// ID returned by `setTimeout`.
+    Timeout(i32),
+}
+
+impl AnimationFrame {
+    /// Returns a future that resolves on the next animation frame.
+    pub fn new() -> Self {
+        Self {
+            fired: Rc::new(Cell::new(false)),
+            waker: Rc::new(RefCell::new(None)),
+            closure: None,
+            handle: None,
+        }
+    }
+
+    /// Schedules the callback for the next animation frame, or after a timeout
+    /// when there is no `window`.
+    fn schedule(closure: &Closure<dyn FnMut()>) -> Result<Handle, JsValue> {
+        let callback = closure.as_ref().unchecked_ref();
+        match web_sys::window() {
+            Some(window) => window
+                .request_animation_frame(callback)
+                .map(Handle::AnimationFrame),
+            None => js_sys::global()
+                .dyn_into::<WorkerGlobalScope>()?
+                .set_timeout_with_callback_and_timeout_and_arguments_0(callback, FRAME_TIMEOUT_MS)
+                .map(Handle::Timeout),
+        }
+    }
+
+    /// Cancels the scheduled callback, so that JS does not call the closure
+    /// after it is freed.
+    fn cancel(handle: Handle) {
+        match handle {
+            Handle::AnimationFrame(id) => {
+                if let Some(window) = web_sys::window() {
+                    // Nothing more can be done if cancelling fails.
+                    let _ = window.cancel_animation_frame(id);
+                }
+            }
+            Handle::Timeout(id) => {
+                if let Ok(scope) = js_sys::global().dyn_into::<WorkerGlobalScope>() {
+                    scope.clear_timeout_with_handle(id);
+                }
+            }
+        }
+    }
+}
+
+impl Future for AnimationFrame {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        if self.fired.get() {
+            return Poll::Ready(());
+        }
+
+        *self.waker.borrow_mut() = Some(cx.waker().clone());
+
+        if self.closure.is_none() {
+            let fired = self.fired.clone();
+            let waker = self.waker.clone();
+            let closure = Closure::wrap(Box::new(move || {
+                fired.set(true);
+                if let Some(waker) = waker.borrow_mut().take() {
+                    waker.wake();
+                }
+            }) as Box<dyn FnMut()>);
+
+            match Self::schedule(&closure) {
+                Ok(handle) => self.handle = Some(handle),
+                Err(_) => return Poll::Ready(()),
+            }
+
+            self.closure = Some(closure);
+        }
+
+        Poll::Pending
+    }
+}
+
+impl Drop for AnimationFrame {
+    fn drop(&mut self) {
+        if let Some(handle) = self.handle.take() {
+            if !self.fired.get() {
+                Self::cancel(handle);
+            }
+        }
+    }
+}
@@ -1,26 +1,86 @@
 use std::error::Error;
 
-use futures::stream::{self, StreamExt, TryStreamExt};
+use futures::{
+    future,
+    stream::{self, StreamExt, TryStreamExt},
+};
 
-use crate::{EventHandlingOutcome, EventLoop};
+use crate::{
+    event_loop::headless::yield_now::yield_now, EventHandler, EventHandlingOutcome, EventLoop,
+};
 
-impl<E> EventLoop<E>
+impl<E, UserEvent> EventLoop<E, UserEvent>
 where
-    E: Error + Send + 'static,
+    E: Error + 'static,
+    UserEvent: 'static,
 {
     /// Runs the event loop until `Exit` is signalled or an error occurs.
     pub async fn run(mut self) -> Result<(), E> {
+        let result = self.run_until_exit().await;
+        self.event_handlers
+            .iter_mut()
+            .for_each(EventHandler::on_exit);
+
+        result
+    }
+
+    async fn run_until_exit(&mut self) -> Result<(), E> {
+        self.state_stack.apply_transitions();
+
         loop {
+            if self.stop_handle.is_stop_requested() {
+                return Ok(());
+            }
+
+            self.receive_user_events().await;
+
             match self.run_once().await {
-                Ok(EventHandlingOutcome::Continue) => {}
+                Ok(EventHandlingOutcome::Continue) => {
+                    if self.is_ended() {
+                        return Ok(());
+                    }
+                    self.state_stack.apply_transitions();
+                    self.idle().await
+                }
                 Ok(EventHandlingOutcome::Exit) => return Ok(()),
                 Err(e) => return Err(e),
             }
         }
     }
 
+    /// Returns whether every event handler has ended, so there is nothing left
+    /// to run.
+    ///
+    /// This is also `true` when there are no event handlers.
+    fn is_ended(&self) -> bool {
+        self.event_handlers.iter().all(EventHandler::is_ended)
+    }
+
+    /// Waits before the next tick.
+    ///
+    /// When every event handler is event driven, this sleeps until one of them
+    /// has an event, or a stop is requested. Otherwise this yields to the
+    /// executor.
+    async fn idle(&mut self) {
+        let event_handler_count = self.event_handlers.len();
+        let is_event_driven = event_handler_count > 0
+            && self
+                .event_handlers
+                .iter()
+                .all(EventHandler::is_event_driven);
+
+        if is_event_driven {
+            future::poll_fn(|cx| self.poll_wake(cx, 0..event_handler_count)).await;
+        } else {
+            yield_now().await;
+        }
+    }
+
     async fn run_once(&mut self) -> Result<EventHandlingOutcome, E> {
-        let stream = stream::iter(self.event_handlers.iter_mut());
+        let handler_groups = &self.handler_groups;
+        let stream = stream::iter(self.event_handlers.iter_mut().filter(|event_handler| {
+            handler_groups.is_event_handler_enabled(event_handler) && !event_handler.is_ended()
+        }));
 
         stream
             .map(Result::<_, E>::Ok)
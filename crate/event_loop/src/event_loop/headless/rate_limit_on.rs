@@ -1,51 +1,125 @@
-use std::error::Error;
+use std::{error::Error, task::Poll, time::Duration};
 
-use futures::stream::{self, Stream, StreamExt};
-use governor::prelude::StreamRateLimitExt;
+use futures::future;
+use futures_timer::Delay;
+use instant::Instant;
 
 use crate::{
-    event_loop::common::{EventHandlersExt, RateLimiter},
-    EventHandlingOutcome, EventLoop,
+    event_loop::{
+        common::{EventHandlersExt, Scheduler},
+        headless::yield_now::yield_now,
+    },
+    EventHandler, EventHandlingOutcome, EventLoop, IdleStrategy,
 };
 
-impl<E> EventLoop<E>
+impl<E, UserEvent> EventLoop<E, UserEvent>
 where
-    E: Error + Send + 'static,
+    E: Error + 'static,
+    UserEvent: 'static,
 {
     /// Runs the event loop until `Exit` is signalled or an error occurs.
     pub async fn run(mut self) -> Result<(), E> {
-        let rate_limiters = self.event_handlers.rate_limiters();
-        let mut event_handler_streams = self.event_handler_streams(&rate_limiters);
-
-        while let Some(index) = event_handler_streams.next().await {
-            let event_handler = &mut self.event_handlers[index];
-            match event_handler.run().await {
-                Ok(EventHandlingOutcome::Continue) => {}
-                Ok(EventHandlingOutcome::Exit) => return Ok(()),
-                Err(e) => return Err(e),
-            }
-        }
+        let result = self.run_until_exit().await;
+        self.event_handlers
+            .iter_mut()
+            .for_each(EventHandler::on_exit);
 
-        #[cfg_attr(tarpaulin, skip)]
-        Ok(())
+        result
     }
 
-    fn event_handler_streams<'r>(
-        &mut self,
-        rate_limiters: &'r [Option<RateLimiter>],
-    ) -> impl Stream<Item = usize> + 'r {
-        let event_handler_streams = rate_limiters
-            .iter()
-            .enumerate()
-            .map(|(index, rate_limiter)| {
-                if let Some(rate_limiter) = rate_limiter {
-                    stream::repeat(index).ratelimit_stream(rate_limiter).boxed()
+    async fn run_until_exit(&mut self) -> Result<(), E> {
+        let mut scheduler = Scheduler::new(self.event_handlers.rate_limiters());
+        let mut due = Vec::with_capacity(self.event_handlers.len());
+        self.state_stack.apply_transitions();
+
+        loop {
+            if self.stop_handle.is_stop_requested() {
+                return Ok(());
+            }
+
+            self.receive_user_events().await;
+
+            let now = Instant::now();
+            let event_handlers = &mut self.event_handlers;
+            future::poll_fn(|cx| {
+                scheduler.unpark(now, |index| event_handlers[index].poll_ready(cx).is_ready());
+                Poll::Ready(())
+            })
+            .await;
+
+            let handler_groups = &self.handler_groups;
+            scheduler.take_due(now, &mut due, |index| {
+                handler_groups.is_event_handler_enabled(&event_handlers[index])
+            });
+
+            for index in due.drain(..) {
+                let event_handler = &mut self.event_handlers[index];
+                match event_handler.run().await {
+                    Ok(EventHandlingOutcome::Continue) => {}
+                    Ok(EventHandlingOutcome::Exit) => return Ok(()),
+                    Err(e) => return Err(e),
+                }
+
+                if event_handler.is_ended() {
+                    // Ended event handlers are not scheduled again.
+                    continue;
+                }
+
+                if event_handler.is_event_driven() {
+                    scheduler.park(index);
                 } else {
-                    stream::repeat(index).boxed()
+                    scheduler.reschedule(index, Instant::now());
                 }
-            })
-            .collect::<Vec<_>>();
+            }
+
+            if self.state_stack.apply_transitions() {
+                let event_handlers = &self.event_handlers;
+                let handler_groups = &self.handler_groups;
+                scheduler.enable(Instant::now(), |index| {
+                    handler_groups.is_event_handler_enabled(&event_handlers[index])
+                });
+            }
+
+            match scheduler.next_deadline() {
+                Some(deadline) => {
+                    let now = Instant::now();
+                    if deadline > now {
+                        match self.idle_strategy {
+                            IdleStrategy::Yield => yield_now().await,
+                            IdleStrategy::Sleep(duration) => {
+                                self.idle(&scheduler, Some(duration)).await
+                            }
+                            IdleStrategy::ParkUntilDeadline => {
+                                self.idle(&scheduler, Some(deadline - now)).await
+                            }
+                        }
+                    } else {
+                        yield_now().await;
+                    }
+                }
+                None if scheduler.has_unscheduled() => self.idle(&scheduler, None).await,
+                #[cfg_attr(tarpaulin, skip)]
+                None => return Ok(()),
+            }
+        }
+    }
 
-        stream::select_all(event_handler_streams)
+    /// Sleeps for the given duration, waking early if a stop is requested or a
+    /// parked event handler has an event.
+    ///
+    /// # Parameters
+    ///
+    /// * `scheduler`: Scheduler with the parked event handlers.
+    /// * `idle_duration`: Duration to sleep for, `None` to sleep until woken.
+    async fn idle(&mut self, scheduler: &Scheduler, idle_duration: Option<Duration>) {
+        let parked = scheduler.parked();
+        let woken = future::poll_fn(|cx| self.poll_wake(cx, parked.iter().copied()));
+
+        match idle_duration {
+            Some(idle_duration) => {
+                future::select(Delay::new(idle_duration), woken).await;
+            }
+            None => woken.await,
+        }
     }
 }
@@ -0,0 +1,43 @@
+#[cfg(not(target_arch = "wasm32"))]
+use core::{
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+#[cfg(target_arch = "wasm32")]
+use crate::event_loop::headless::animation_frame::AnimationFrame;
+
+/// Yields to the executor, so that other tasks may run.
+///
+/// On WASM, this waits for the next animation frame, as the browser cannot run
+/// until the event loop returns control to it.
+pub(crate) async fn yield_now() {
+    #[cfg(not(target_arch = "wasm32"))]
+    YieldNow { yielded: false }.await;
+
+    #[cfg(target_arch = "wasm32")]
+    AnimationFrame::new().await;
+}
+
+/// Future that is pending the first time it is polled.
+#[cfg(not(target_arch = "wasm32"))]
+struct YieldNow {
+    /// Whether this future has returned `Pending`.
+    yielded: bool,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl Future for YieldNow {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        if self.yielded {
+            Poll::Ready(())
+        } else {
+            self.yielded = true;
+            cx.waker().wake_by_ref();
+            Poll::Pending
+        }
+    }
+}
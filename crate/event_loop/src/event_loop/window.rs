@@ -6,18 +6,37 @@ use std::{
     error::Error,
     future::Future,
     ops::{Deref, DerefMut},
+    task::{Context, Poll, Waker},
 };
+#[cfg(feature = "input_replay")]
+use std::{io, path::Path};
 
+use futures::{
+    channel::mpsc::{self, UnboundedReceiver, UnboundedSender},
+    stream::StreamExt,
+};
+#[cfg(feature = "input_replay")]
+use instant::Instant;
 use winit::event_loop::EventLoop as WinitEventLoop;
 
-use crate::EventHandler;
+#[cfg(feature = "input_replay")]
+use crate::input_recorder::InputRecorder;
+#[cfg(feature = "config")]
+use crate::{ConfigError, EventLoopConfig};
+use crate::{
+    EventBus, EventHandler, EventLoopProxy, EventWriter, ExitReason, HandlerGroups, Introspection,
+    LoopEvent, State, StateStack, StopHandle,
+};
 
+#[cfg(feature = "rate_limit")]
+mod frame_pacing;
+mod loop_waker;
 #[cfg(not(feature = "rate_limit"))]
 mod rate_limit_off;
 #[cfg(feature = "rate_limit")]
 mod rate_limit_on;
 
-type ExitHandler<E> = Box<dyn FnOnce(Option<E>) -> Pin<Box<dyn Future<Output = ()>>>>;
+type ExitHandler<E> = Box<dyn FnOnce(ExitReason<E>) -> Pin<Box<dyn Future<Output = ()>>>>;
 
 /// Receives events and runs an event handler function.
 ///
@@ -32,7 +51,9 @@ type ExitHandler<E> = Box<dyn FnOnce(Option<E>) -> Pin<Box<dyn Future<Output = (
 /// # Type Parameters
 ///
 /// * `E`: Error type.
-/// * `UserEvent`: Custom user event type, defaults to `()`.
+/// * `UserEvent`: Custom user event type, defaults to `()`. These are sent
+///   through an `EventLoopProxy`, which wakes the `winit` event loop with
+///   `LoopEvent::Wake`.
 pub struct EventLoop<E, UserEvent = ()>
 where
     UserEvent: 'static,
@@ -40,9 +61,26 @@ where
     /// `EventHandler`s to run during event loop execution.
     event_handlers: Vec<EventHandler<E>>,
     /// The `winit` event loop to run.
-    winit_event_loop: WinitEventLoop<UserEvent>,
+    winit_event_loop: WinitEventLoop<LoopEvent>,
     /// Task to run on exit.
     exit_handler: Option<ExitHandler<E>>,
+    /// Requests the event loop to exit.
+    stop_handle: StopHandle,
+    /// Channels between event handlers.
+    event_bus: EventBus,
+    /// Groups of event handlers that are disabled.
+    handler_groups: HandlerGroups,
+    /// Stack of game states, whose event handlers run while current.
+    state_stack: StateStack,
+    /// Lists the event handlers while the event loop is running.
+    introspection: Introspection,
+    /// Sends user events, cloned into each `EventLoopProxy`.
+    user_event_tx: UnboundedSender<UserEvent>,
+    /// Receives user events from `EventLoopProxy`s.
+    user_event_rx: UnboundedReceiver<UserEvent>,
+    /// Records input and tick timestamps to a file.
+    #[cfg(feature = "input_replay")]
+    input_recorder: Option<InputRecorder>,
     /// Whether the event loop is run in the main thread.
     is_in_main_thread: bool,
 }
@@ -63,6 +101,15 @@ where
         } else {
             debug_struct.field("exit_handler", &"None");
         }
+        debug_struct.field("stop_handle", &self.stop_handle);
+        debug_struct.field("event_bus", &self.event_bus);
+        debug_struct.field("handler_groups", &self.handler_groups);
+        debug_struct.field("state_stack", &self.state_stack);
+        debug_struct.field("introspection", &self.introspection);
+        debug_struct.field("user_event_tx", &self.user_event_tx);
+        debug_struct.field("user_event_rx", &self.user_event_rx);
+        #[cfg(feature = "input_replay")]
+        debug_struct.field("input_recorder", &self.input_recorder);
         debug_struct.field("is_in_main_thread", &self.is_in_main_thread);
 
         debug_struct.finish()
@@ -71,7 +118,7 @@ where
 
 impl<E> EventLoop<E, ()>
 where
-    E: Error,
+    E: Error + 'static,
 {
     /// Returns a new `EventLoop`.
     ///
@@ -80,17 +127,13 @@ where
     /// * `event_handlers`: The logic to run for each event loop execution.
     #[cfg_attr(tarpaulin, skip)]
     pub fn new(event_handlers: Vec<EventHandler<E>>) -> Self {
-        let winit_event_loop = WinitEventLoop::with_user_event();
-
-        Self {
-            event_handlers,
-            winit_event_loop,
-            exit_handler: None,
-            is_in_main_thread: true,
-        }
+        Self::new_with_event(event_handlers)
     }
 
-    /// Returns a new `EventLoop`.
+    /// Returns a new `EventLoop` that receives custom user events.
+    ///
+    /// Unlike `new`, the user event type is not `()`, so user events sent
+    /// through `EventLoop::create_proxy` may carry data.
     ///
     /// # Parameters
     ///
@@ -99,35 +142,77 @@ where
     pub fn new_with_event<UserEvent>(
         event_handlers: Vec<EventHandler<E>>,
     ) -> EventLoop<E, UserEvent> {
-        let winit_event_loop = WinitEventLoop::with_user_event();
-
-        EventLoop::<E, UserEvent> {
-            event_handlers,
-            winit_event_loop,
-            exit_handler: None,
-            is_in_main_thread: true,
-        }
+        EventLoop::from_winit(WinitEventLoop::with_user_event(), event_handlers)
     }
 
-    /// Returns a new `EventLoop`.
+    /// Returns a new `EventLoop` that may be created outside the main thread,
+    /// such as in tests.
     ///
     /// # Parameters
     ///
     /// * `event_handlers`: The logic to run for each event loop execution.
     #[cfg(any(unix, windows))]
     pub fn new_any_thread(event_handlers: Vec<EventHandler<E>>) -> Self {
+        Self::new_any_thread_with_event(event_handlers)
+    }
+
+    /// Returns a new `EventLoop` that may be created outside the main thread,
+    /// and receives custom user events.
+    ///
+    /// # Parameters
+    ///
+    /// * `event_handlers`: The logic to run for each event loop execution.
+    #[cfg(any(unix, windows))]
+    pub fn new_any_thread_with_event<UserEvent>(
+        event_handlers: Vec<EventHandler<E>>,
+    ) -> EventLoop<E, UserEvent> {
         #[cfg(unix)]
         use winit::platform::unix::EventLoopExtUnix;
         #[cfg(windows)]
         use winit::platform::windows::EventLoopExtWindows;
 
-        let winit_event_loop = WinitEventLoop::new_any_thread();
+        EventLoop {
+            is_in_main_thread: false,
+            ..EventLoop::from_winit(WinitEventLoop::new_any_thread(), event_handlers)
+        }
+    }
+}
+
+impl<E, UserEvent> EventLoop<E, UserEvent>
+where
+    E: Error + 'static,
+    UserEvent: 'static,
+{
+    /// Returns a new `EventLoop` that runs the given `winit` event loop.
+    ///
+    /// # Parameters
+    ///
+    /// * `winit_event_loop`: The `winit` event loop to run.
+    /// * `event_handlers`: The logic to run for each event loop execution.
+    fn from_winit(
+        winit_event_loop: WinitEventLoop<LoopEvent>,
+        event_handlers: Vec<EventHandler<E>>,
+    ) -> Self {
+        let handler_groups = HandlerGroups::new();
+        let state_stack = StateStack::new(handler_groups.clone());
+        let introspection = Introspection::new(handler_groups.clone());
+        introspection.register(&event_handlers);
+        let (user_event_tx, user_event_rx) = mpsc::unbounded();
 
         Self {
             event_handlers,
             winit_event_loop,
             exit_handler: None,
-            is_in_main_thread: false,
+            stop_handle: StopHandle::new(),
+            event_bus: EventBus::new(),
+            handler_groups,
+            state_stack,
+            introspection,
+            user_event_tx,
+            user_event_rx,
+            #[cfg(feature = "input_replay")]
+            input_recorder: None,
+            is_in_main_thread: true,
         }
     }
 }
@@ -139,6 +224,8 @@ where
 {
     /// Sets a function to run when the event loop exits.
     ///
+    /// The function is passed the reason the event loop exited.
+    ///
     /// There is only one exit event handler, so setting this twice will replace
     /// the first one.
     pub fn with_exit_handler<FnExitHandler, ExitHandler>(
@@ -146,28 +233,221 @@ where
         fn_exit_handler: FnExitHandler,
     ) -> Self
     where
-        FnExitHandler: FnOnce(Option<E>) -> ExitHandler + 'static,
+        FnExitHandler: FnOnce(ExitReason<E>) -> ExitHandler + 'static,
         ExitHandler: Future<Output = ()> + 'static,
     {
-        self.exit_handler = Some(Box::new(|event_handling_outcome| {
-            Box::pin(fn_exit_handler(event_handling_outcome))
+        self.exit_handler = Some(Box::new(|exit_reason| {
+            Box::pin(fn_exit_handler(exit_reason))
         }));
         self
     }
 
+    /// Returns a handle to request the event loop to exit.
+    ///
+    /// The stop request is honoured on the next `winit` loop iteration, and
+    /// its reason is passed to the exit handler.
+    pub fn stop_handle(&self) -> StopHandle {
+        self.stop_handle.clone()
+    }
+
+    /// Returns the event bus, which event handlers use to send events to each
+    /// other.
+    pub fn event_bus(&self) -> EventBus {
+        self.event_bus.clone()
+    }
+
+    /// Returns the handle to enable and disable groups of event handlers.
+    pub fn handler_groups(&self) -> HandlerGroups {
+        self.handler_groups.clone()
+    }
+
+    /// Returns the stack of game states, which event handlers use to
+    /// transition between states.
+    pub fn state_stack(&self) -> StateStack {
+        self.state_stack.clone()
+    }
+
+    /// Returns the handle to list the event handlers and their status, which
+    /// may be sent to other threads.
+    pub fn introspection(&self) -> Introspection {
+        self.introspection.clone()
+    }
+
+    /// Returns a proxy to send user events to this event loop from any thread.
+    ///
+    /// User events are emitted to the event bus before event handlers are
+    /// next run, and read by event handlers through an
+    /// `EventReader<UserEvent>`.
+    pub fn create_proxy(&self) -> EventLoopProxy<UserEvent> {
+        EventLoopProxy::new(self.user_event_tx.clone())
+    }
+
+    /// Adds an event handler to run after the existing event handlers.
+    ///
+    /// This allows event handlers to be created after obtaining handles from
+    /// the event loop, such as its `EventBus`.
+    ///
+    /// # Parameters
+    ///
+    /// * `event_handler`: The event handler to add.
+    pub fn with_event_handler(mut self, event_handler: EventHandler<E>) -> Self
+    where
+        E: 'static,
+    {
+        self.event_handlers.push(event_handler);
+        self.introspection.register(&self.event_handlers);
+        self
+    }
+
+    /// Adds a state, whose event handlers run while it is the current state.
+    ///
+    /// The state's event handlers are added to the group named after the
+    /// state. The state is entered by pushing it onto the `StateStack`.
+    ///
+    /// # Parameters
+    ///
+    /// * `state`: The state to add.
+    pub fn with_state(mut self, state: State<E>) -> Self
+    where
+        E: 'static,
+    {
+        self.state_stack.add_state(state, &mut self.event_handlers);
+        self.introspection.register(&self.event_handlers);
+        self
+    }
+
+    /// Overrides event handler settings with those declared in a
+    /// configuration, keyed by event handler name.
+    ///
+    /// This should be called after every event handler and state is added, as
+    /// event handlers added afterwards are not configured.
+    ///
+    /// # Parameters
+    ///
+    /// * `config`: The configuration, typically from `EventLoopConfig::load`.
+    /// * `error_reporter`: Function to report errors of event handlers that
+    ///   the configuration sets to skip them, e.g. to a logger.
+    #[cfg(feature = "config")]
+    #[cfg_attr(feature = "docs", doc(cfg(config)))]
+    pub fn with_config(
+        mut self,
+        config: &EventLoopConfig,
+        error_reporter: fn(&str, &dyn Error),
+    ) -> Result<Self, ConfigError>
+    where
+        E: 'static,
+    {
+        config.apply(&mut self.event_handlers, error_reporter)?;
+        self.introspection.register(&self.event_handlers);
+        Ok(self)
+    }
+
+    /// Records input, tick timestamps, and the event handlers run in each
+    /// tick to a file, replacing it if it exists.
+    ///
+    /// The recording is replayed through event handlers with `InputReplay`. If
+    /// the recording cannot be written while the event loop runs, the error is
+    /// passed to the reporter and recording stops.
+    ///
+    /// # Parameters
+    ///
+    /// * `path`: Path to the recording file.
+    /// * `reporter`: Function to report failures to write the recording, e.g.
+    ///   to a logger.
+    #[cfg(feature = "input_replay")]
+    #[cfg_attr(feature = "docs", doc(cfg(input_replay)))]
+    pub fn with_input_recording<P>(mut self, path: P, reporter: fn(&io::Error)) -> io::Result<Self>
+    where
+        P: AsRef<Path>,
+    {
+        self.input_recorder = Some(InputRecorder::new(path.as_ref(), reporter)?);
+        Ok(self)
+    }
+
     /// Returns the `WinitEventLoop`.
     #[cfg_attr(tarpaulin, skip)]
-    pub fn winit_event_loop(&self) -> &WinitEventLoop<UserEvent> {
+    pub fn winit_event_loop(&self) -> &WinitEventLoop<LoopEvent> {
         &self.winit_event_loop
     }
 }
 
+cfg_spawner! {
+    impl<E, UserEvent> EventLoop<E, UserEvent>
+    where
+        UserEvent: 'static,
+    {
+        /// Returns a handle to spawn background tasks on the executor selected
+        /// by cargo features.
+        pub fn spawner(&self) -> crate::Spawner {
+            crate::Spawner::new()
+        }
+    }
+}
+
+impl<E, UserEvent> EventLoop<E, UserEvent>
+where
+    UserEvent: 'static,
+{
+    /// Emits user events received from `EventLoopProxy`s to the event bus, and
+    /// registers the waker to wake the `winit` event loop when more are sent.
+    ///
+    /// # Parameters
+    ///
+    /// * `user_event_rx`: Receives user events from `EventLoopProxy`s.
+    /// * `user_event_writer`: Emits user events to the event bus.
+    /// * `waker`: Wakes the `winit` event loop.
+    fn receive_user_events(
+        user_event_rx: &mut UnboundedReceiver<UserEvent>,
+        user_event_writer: &EventWriter<UserEvent>,
+        waker: &Waker,
+    ) {
+        let mut cx = Context::from_waker(waker);
+        while let Poll::Ready(Some(user_event)) = user_event_rx.poll_next_unpin(&mut cx) {
+            user_event_writer.emit(user_event);
+        }
+    }
+}
+
+#[cfg(feature = "input_replay")]
+impl<E, UserEvent> EventLoop<E, UserEvent>
+where
+    UserEvent: 'static,
+{
+    /// Records the input received since the last tick and the event handlers
+    /// that run in this tick, stopping the recording if it cannot be written.
+    ///
+    /// # Parameters
+    ///
+    /// * `input_recorder`: Records input, if recording.
+    /// * `event_handlers`: Indices of the event handlers that run in the tick.
+    fn record_tick<I>(input_recorder: &mut Option<InputRecorder>, event_handlers: I)
+    where
+        I: IntoIterator<Item = usize>,
+    {
+        if let Some(recorder) = input_recorder.as_mut() {
+            if let Err(error) = recorder.record_tick(Instant::now(), event_handlers) {
+                (recorder.reporter())(&error);
+                *input_recorder = None;
+            }
+        }
+    }
+
+    /// Writes the remaining input to the recording file.
+    fn finish_recording(input_recorder: &mut Option<InputRecorder>) {
+        if let Some(mut recorder) = input_recorder.take() {
+            if let Err(error) = recorder.flush() {
+                (recorder.reporter())(&error);
+            }
+        }
+    }
+}
+
 impl<E, UserEvent> Deref for EventLoop<E, UserEvent>
 where
     E: Error,
     UserEvent: 'static,
 {
-    type Target = WinitEventLoop<UserEvent>;
+    type Target = WinitEventLoop<LoopEvent>;
 
     #[cfg_attr(tarpaulin, skip)]
     fn deref(&self) -> &Self::Target {
@@ -185,135 +465,3 @@ where
         &mut self.winit_event_loop
     }
 }
-
-#[cfg(test)]
-mod tests {
-    use std::thread;
-    #[cfg(feature = "rate_limit")]
-    use std::time::Duration;
-
-    use crossbeam_channel::{SendError, Sender};
-
-    #[cfg(feature = "rate_limit")]
-    use crate::RateLimit;
-    use crate::{EventHandler, EventHandlingOutcome, EventLoop};
-
-    #[test]
-    fn run_runs_event_handlers_until_exit_is_signalled() -> Result<(), SendError<()>> {
-        let (done_tx, done_rx) = crossbeam_channel::bounded(1);
-        let (tx, rx) = crossbeam_channel::bounded(10);
-
-        thread::spawn(|| {
-            let event_handler_send = sender(tx);
-            let event_handler_countdown = countdown(3);
-
-            let event_loop =
-                EventLoop::new_any_thread(vec![event_handler_send, event_handler_countdown])
-                    .with_exit_handler(|e| async move {
-                        let _ = done_tx.send(e);
-                    });
-
-            smol::run(event_loop.run());
-        });
-        let run_result = done_rx.recv();
-        assert_eq!(Ok(None), run_result);
-
-        let count = rx.try_iter().collect::<Vec<()>>().len();
-        assert_eq!(3, count);
-
-        Ok(())
-    }
-
-    #[test]
-    fn run_returns_on_first_error() -> Result<(), SendError<()>> {
-        let (done_tx, done_rx) = crossbeam_channel::bounded(1);
-        let (tx, _rx) = crossbeam_channel::bounded(10);
-
-        thread::spawn(|| {
-            let event_handler_send = sender(tx);
-            let event_handler_countdown = countdown(3);
-
-            let event_loop = EventLoop::new_any_thread(vec![
-                event_handler_send,
-                errorer(),
-                event_handler_countdown,
-            ])
-            .with_exit_handler(|e| async move {
-                let _ = done_tx.send(e);
-            });
-
-            smol::run(event_loop.run());
-        });
-
-        let run_result = done_rx.recv();
-        assert_eq!(Ok(Some(SendError(()))), run_result);
-
-        Ok(())
-    }
-
-    #[cfg(feature = "rate_limit")]
-    #[test]
-    fn event_handlers_are_rate_limited_independently() -> Result<(), SendError<()>> {
-        let (done_tx, done_rx) = crossbeam_channel::bounded(1);
-        let (tx0, rx0) = crossbeam_channel::unbounded();
-        let (tx1, rx1) = crossbeam_channel::bounded(10);
-
-        thread::spawn(|| {
-            let event_handler_send_0 = sender(tx0);
-
-            let event_handler_send_1 =
-                sender(tx1).with_rate_limit(RateLimit::interval(Duration::from_millis(2)));
-            let event_handler_countdown =
-                countdown(3).with_rate_limit(RateLimit::interval(Duration::from_millis(3)));
-
-            let event_loop = EventLoop::new_any_thread(vec![
-                event_handler_countdown,
-                event_handler_send_1,
-                event_handler_send_0,
-            ])
-            .with_exit_handler(|e| async move {
-                let _ = done_tx.send(e);
-            });
-            smol::run(event_loop.run());
-        });
-
-        let _ = done_rx.recv();
-
-        let count_0 = rx0.try_iter().collect::<Vec<()>>().len();
-        let count_1 = rx1.try_iter().collect::<Vec<()>>().len();
-
-        assert!(count_0 >= 8, "count_0: {}", count_0);
-        assert!(count_1 >= 2, "count_1: {}", count_1);
-        assert!(count_1 <= 6, "count_1: {}", count_1);
-
-        Ok(())
-    }
-
-    fn sender(tx: Sender<()>) -> EventHandler<SendError<()>> {
-        EventHandler::<SendError<()>>::new(move || {
-            let tx = tx.clone();
-            async move {
-                tx.send(())?;
-
-                Ok(EventHandlingOutcome::Continue)
-            }
-        })
-    }
-
-    fn countdown(mut count: u32) -> EventHandler<SendError<()>> {
-        EventHandler::<SendError<()>>::new(move || {
-            count = count.saturating_sub(1);
-            async move {
-                if count > 0 {
-                    Ok(EventHandlingOutcome::Continue)
-                } else {
-                    Ok(EventHandlingOutcome::Exit)
-                }
-            }
-        })
-    }
-
-    fn errorer() -> EventHandler<SendError<()>> {
-        EventHandler::<SendError<()>>::new(|| async move { Err(SendError(())) })
-    }
-}
@@ -0,0 +1,131 @@
+use std::num::NonZeroU32;
+#[cfg(not(target_arch = "wasm32"))]
+use std::time::Duration;
+
+use instant::Instant;
+use winit::event_loop::{ControlFlow, EventLoop as WinitEventLoop};
+
+use crate::event_loop::common::Scheduler;
+
+/// How long before a frame boundary the event loop stops sleeping and starts
+/// spinning.
+///
+/// Operating system timers may wake up late by about a millisecond, so
+/// sleeping all the way to the frame boundary produces uneven frame spacing.
+#[cfg(not(target_arch = "wasm32"))]
+const SPIN_MARGIN: Duration = Duration::from_millis(2);
+
+/// How long before a frame boundary the event loop stops sleeping within the
+/// spin margin, and spins until the boundary.
+#[cfg(not(target_arch = "wasm32"))]
+const SPIN_DURATION: Duration = Duration::from_micros(500);
+
+/// Returns the refresh rate of the primary monitor, if known.
+///
+/// The refresh rate is taken from the fastest video mode that matches the
+/// monitor's current size.
+pub(super) fn display_refresh_rate<UserEvent>(
+    winit_event_loop: &WinitEventLoop<UserEvent>,
+) -> Option<NonZeroU32> {
+    let monitor = winit_event_loop.primary_monitor();
+    let monitor_size = monitor.size();
+
+    monitor
+        .video_modes()
+        .filter(|video_mode| video_mode.size() == monitor_size)
+        .map(|video_mode| video_mode.refresh_rate())
+        .max()
+        .and_then(|refresh_rate| NonZeroU32::new(u32::from(refresh_rate)))
+}
+
+/// Waits until the next frame boundary, if it is within the spin margin.
+#[cfg(not(target_arch = "wasm32"))]
+pub(super) fn spin_until_frame(scheduler: &Scheduler) {
+    if let Some(frame_deadline) = scheduler.next_frame_deadline() {
+        let now = Instant::now();
+        if frame_deadline > now && frame_deadline - now <= SPIN_MARGIN {
+            wait_until(frame_deadline);
+        }
+    }
+}
+
+/// Sleeps until shortly before the deadline, then spins until it is reached.
+///
+/// Sleeping for most of the wait avoids occupying a CPU core, and spinning for
+/// the rest avoids waking up late.
+///
+/// # Parameters
+///
+/// * `deadline`: Instant to wait until.
+#[cfg(not(target_arch = "wasm32"))]
+fn wait_until(deadline: Instant) {
+    if let Some(sleep_duration) = deadline
+        .checked_duration_since(Instant::now())
+        .and_then(|remaining| remaining.checked_sub(SPIN_DURATION))
+    {
+        std::thread::sleep(sleep_duration);
+    }
+
+    while Instant::now() < deadline {
+        std::hint::spin_loop();
+    }
+}
+
+/// Returns the `ControlFlow` to wait for the next event handler to be due.
+///
+/// When the next event handler is frame paced, natively the event loop wakes
+/// before the frame boundary to spin until it, and on WASM the event loop
+/// polls, which `winit` drives using `requestAnimationFrame`.
+///
+/// When every event handler is parked or disabled, the event loop waits until
+/// one is woken.
+pub(super) fn control_flow(scheduler: &Scheduler) -> ControlFlow {
+    let now = Instant::now();
+    match scheduler.next_deadline() {
+        Some(deadline) if deadline > now => {
+            if scheduler.next_frame_deadline().is_some() {
+                #[cfg(not(target_arch = "wasm32"))]
+                {
+                    deadline
+                        .checked_sub(SPIN_MARGIN)
+                        .filter(|wake_at| *wake_at > now)
+                        .map_or(ControlFlow::Poll, ControlFlow::WaitUntil)
+                }
+
+                #[cfg(target_arch = "wasm32")]
+                {
+                    ControlFlow::Poll
+                }
+            } else {
+                ControlFlow::WaitUntil(deadline)
+            }
+        }
+        None if scheduler.has_unscheduled() => ControlFlow::Wait,
+        _ => ControlFlow::Poll,
+    }
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use std::time::Duration;
+
+    use instant::Instant;
+
+    use super::wait_until;
+
+    #[test]
+    fn wait_until_does_not_return_before_deadline() {
+        let deadline = Instant::now() + Duration::from_millis(2);
+
+        wait_until(deadline);
+
+        // The upper bound is generous, as loaded machines may deschedule the thread.
+        let now = Instant::now();
+        assert!(now >= deadline, "woke up early by: {:?}", deadline - now);
+        assert!(
+            now - deadline < Duration::from_secs(1),
+            "woke up late by: {:?}",
+            now - deadline
+        );
+    }
+}
@@ -0,0 +1,48 @@
+use std::{
+    sync::{Arc, Mutex},
+    task::Waker,
+};
+
+use futures::task::{self, ArcWake};
+use winit::event_loop::EventLoopProxy;
+
+use crate::LoopEvent;
+
+/// Wakes the `winit` event loop when an event driven event handler has an
+/// event, a user event is sent, or a stop is requested.
+pub(super) struct LoopWaker {
+    /// Proxy to send the wake event through.
+    ///
+    /// This is in a `Mutex` as wakers must be `Sync`.
+    event_loop_proxy: Mutex<EventLoopProxy<LoopEvent>>,
+}
+
+// `winit`'s proxy is not `Send` on WASM, as it refers to the browser's event
+// loop. WASM runs the event loop on a single thread, so the waker is only used
+// on that thread.
+#[cfg(target_arch = "wasm32")]
+unsafe impl Send for LoopWaker {}
+#[cfg(target_arch = "wasm32")]
+unsafe impl Sync for LoopWaker {}
+
+impl LoopWaker {
+    /// Returns a `Waker` that sends `LoopEvent::Wake` through the proxy.
+    ///
+    /// # Parameters
+    ///
+    /// * `event_loop_proxy`: Proxy to the `winit` event loop.
+    pub fn waker(event_loop_proxy: EventLoopProxy<LoopEvent>) -> Waker {
+        task::waker(Arc::new(Self {
+            event_loop_proxy: Mutex::new(event_loop_proxy),
+        }))
+    }
+}
+
+impl ArcWake for LoopWaker {
+    fn wake_by_ref(arc_self: &Arc<Self>) {
+        if let Ok(event_loop_proxy) = arc_self.event_loop_proxy.lock() {
+            // The event loop may have exited, in which case there is nothing to wake.
+            let _ = event_loop_proxy.send_event(LoopEvent::Wake);
+        }
+    }
+}
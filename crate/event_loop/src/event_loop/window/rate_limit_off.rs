@@ -1,6 +1,10 @@
-use std::error::Error;
+use std::{
+    error::Error,
+    task::{Context, Waker},
+};
 
 use futures::{
+    channel::mpsc::UnboundedReceiver,
     executor::LocalPool,
     stream::{self, StreamExt, TryStreamExt},
 };
@@ -9,21 +13,45 @@ use winit::{
     event_loop::{ControlFlow, EventLoopWindowTarget},
 };
 
+use super::loop_waker::LoopWaker;
 use super::ExitHandler;
-use crate::{EventHandler, EventHandlingOutcome, EventLoop};
+#[cfg(feature = "input_replay")]
+use crate::input_recorder::InputRecorder;
+use crate::{
+    EventHandler, EventHandlingOutcome, EventLoop, EventWriter, ExitReason, HandlerGroups,
+    InputEvent, LoopEvent, StateStack, StopHandle,
+};
 
 #[cfg(not(target_arch = "wasm32"))]
-struct EventLoopParams<'f, E> {
+struct EventLoopParams<'f, E, UserEvent> {
     event_handlers: &'f mut [EventHandler<E>],
     exit_handler: &'f mut Option<ExitHandler<E>>,
+    stop_handle: StopHandle,
+    handler_groups: HandlerGroups,
+    state_stack: StateStack,
+    user_event_rx: &'f mut UnboundedReceiver<UserEvent>,
+    user_event_writer: EventWriter<UserEvent>,
+    input_event_writer: EventWriter<InputEvent>,
+    #[cfg(feature = "input_replay")]
+    input_recorder: &'f mut Option<InputRecorder>,
+    waker: Waker,
     local_pool: &'f mut LocalPool,
     should_exit: &'f mut bool,
 }
 
 #[cfg(target_arch = "wasm32")]
-struct EventLoopParams<'f, E> {
+struct EventLoopParams<'f, E, UserEvent> {
     event_handlers: Vec<EventHandler<E>>,
     exit_handler: Option<ExitHandler<E>>,
+    stop_handle: StopHandle,
+    handler_groups: HandlerGroups,
+    state_stack: StateStack,
+    user_event_rx: UnboundedReceiver<UserEvent>,
+    user_event_writer: EventWriter<UserEvent>,
+    input_event_writer: EventWriter<InputEvent>,
+    #[cfg(feature = "input_replay")]
+    input_recorder: Option<InputRecorder>,
+    waker: Waker,
     local_pool: LocalPool,
     should_exit: bool,
     marker: std::marker::PhantomData<&'f E>,
@@ -31,7 +59,7 @@ struct EventLoopParams<'f, E> {
 
 impl<E, UserEvent> EventLoop<E, UserEvent>
 where
-    E: Error + Send + 'static,
+    E: Error + 'static,
     UserEvent: 'static,
 {
     /// Runs the event loop until `Exit` is signalled or an error occurs.
@@ -47,13 +75,31 @@ where
             mut event_handlers,
             mut winit_event_loop,
             mut exit_handler,
+            stop_handle,
+            event_bus,
+            handler_groups,
+            state_stack,
+            introspection: _,
+            user_event_tx: _,
+            mut user_event_rx,
+            #[cfg(feature = "input_replay")]
+            mut input_recorder,
             is_in_main_thread,
         } = self;
 
+        state_stack.apply_transitions();
+
+        let user_event_writer = event_bus.writer::<UserEvent>();
+        let input_event_writer = event_bus.writer::<InputEvent>();
+        let waker = LoopWaker::waker(winit_event_loop.create_proxy());
+        stop_handle.wake_on_stop(waker.clone());
         let mut local_pool = LocalPool::new();
 
         let event_handlers = &mut event_handlers;
         let exit_handler = &mut exit_handler;
+        let user_event_rx = &mut user_event_rx;
+        #[cfg(feature = "input_replay")]
+        let input_recorder = &mut input_recorder;
         let local_pool = &mut local_pool;
         let mut should_exit = false;
 
@@ -62,6 +108,15 @@ where
             let event_loop_params = EventLoopParams {
                 event_handlers,
                 exit_handler,
+                stop_handle: stop_handle.clone(),
+                handler_groups: handler_groups.clone(),
+                state_stack: state_stack.clone(),
+                user_event_rx,
+                user_event_writer: user_event_writer.clone(),
+                input_event_writer: input_event_writer.clone(),
+                #[cfg(feature = "input_replay")]
+                input_recorder,
+                waker: waker.clone(),
                 local_pool,
                 should_exit,
             };
@@ -82,11 +137,33 @@ where
             event_handlers,
             winit_event_loop,
             exit_handler,
+            stop_handle,
+            event_bus,
+            handler_groups,
+            state_stack,
+            introspection: _,
+            user_event_rx,
+            #[cfg(feature = "input_replay")]
+            input_recorder,
+            ..
         } = self;
 
+        state_stack.apply_transitions();
+
+        let waker = LoopWaker::waker(winit_event_loop.create_proxy());
+        stop_handle.wake_on_stop(waker.clone());
         let event_loop_params = EventLoopParams {
             event_handlers,
             exit_handler,
+            stop_handle,
+            handler_groups,
+            state_stack,
+            user_event_rx,
+            user_event_writer: event_bus.writer::<UserEvent>(),
+            input_event_writer: event_bus.writer::<InputEvent>(),
+            #[cfg(feature = "input_replay")]
+            input_recorder,
+            waker,
             local_pool: LocalPool::new(),
             should_exit: false,
             marker: std::marker::PhantomData,
@@ -96,48 +173,135 @@ where
     }
 
     fn fn_event_loop<'f>(
-        mut event_loop_params: EventLoopParams<'f, E>,
-    ) -> impl FnMut(Event<UserEvent>, &EventLoopWindowTarget<UserEvent>, &mut ControlFlow) + 'f
+        mut event_loop_params: EventLoopParams<'f, E, UserEvent>,
+    ) -> impl FnMut(Event<LoopEvent>, &EventLoopWindowTarget<LoopEvent>, &mut ControlFlow) + 'f
     {
-        move |_event, _, control_flow| {
+        move |event, _, control_flow| {
             // We cannot run event handlers in this closure, as it isn't `async`, but we can
             // submit them to a local executor to be run on the main thread.
+            //
+            // Event handlers are run once per `winit` loop iteration. On WASM, polling is
+            // driven by `requestAnimationFrame`, so this is once per animation frame.
 
             let EventLoopParams {
                 ref mut event_handlers,
                 ref mut exit_handler,
+                ref stop_handle,
+                ref handler_groups,
+                ref state_stack,
+                ref mut user_event_rx,
+                ref user_event_writer,
+                ref input_event_writer,
+                #[cfg(feature = "input_replay")]
+                ref mut input_recorder,
+                ref waker,
                 ref mut local_pool,
                 ref mut should_exit,
                 ..
             } = event_loop_params;
 
-            if !**should_exit {
-                let event_handlers_task = Self::run_once(event_handlers);
-
-                // Run the event handlers
-                let event_handling_outcome = local_pool.run_until(event_handlers_task);
-                let mut error = None;
-                *control_flow = match event_handling_outcome {
-                    Ok(EventHandlingOutcome::Continue) => ControlFlow::Poll,
-                    Ok(EventHandlingOutcome::Exit) => ControlFlow::Exit,
-                    Err(e) => {
-                        error = Some(e);
-                        ControlFlow::Exit
+            if let Some(input_event) = InputEvent::from_event(&event) {
+                #[cfg(feature = "input_replay")]
+                {
+                    if let Some(input_recorder) = input_recorder.as_mut() {
+                        input_recorder.record_event(input_event);
+                    }
+                }
+                input_event_writer.emit(input_event);
+                return;
+            }
+
+            if !**should_exit && matches!(event, Event::MainEventsCleared) {
+                let exit_reason = if stop_handle.is_stop_requested() {
+                    Some(ExitReason::Stopped(stop_handle.reason()))
+                } else {
+                    #[cfg(feature = "input_replay")]
+                    Self::record_tick(
+                        input_recorder,
+                        event_handlers
+                            .iter()
+                            .enumerate()
+                            .filter(|(_, event_handler)| {
+                                handler_groups.is_event_handler_enabled(event_handler)
+                                    && !event_handler.is_ended()
+                            })
+                            .map(|(index, _)| index),
+                    );
+
+                    Self::receive_user_events(user_event_rx, user_event_writer, waker);
+
+                    let event_handlers_task = Self::run_once(event_handlers, handler_groups);
+
+                    // Run the event handlers
+                    let event_handling_outcome = local_pool.run_until(event_handlers_task);
+                    match event_handling_outcome {
+                        Ok(EventHandlingOutcome::Continue)
+                            if !event_handlers.is_empty()
+                                && event_handlers.iter().all(EventHandler::is_ended) =>
+                        {
+                            Some(ExitReason::Exit)
+                        }
+                        Ok(EventHandlingOutcome::Continue) => {
+                            state_stack.apply_transitions();
+                            *control_flow =
+                                Self::control_flow(event_handlers, handler_groups, waker);
+                            None
+                        }
+                        Ok(EventHandlingOutcome::Exit) => Some(ExitReason::Exit),
+                        Err(e) => Some(ExitReason::Error(e)),
                     }
                 };
 
-                if *control_flow == ControlFlow::Exit {
+                if let Some(exit_reason) = exit_reason {
+                    *control_flow = ControlFlow::Exit;
                     **should_exit = true;
+                    #[cfg(feature = "input_replay")]
+                    Self::finish_recording(input_recorder);
+                    event_handlers.iter_mut().for_each(EventHandler::on_exit);
                     if let Some(exit_handler) = exit_handler.take() {
-                        local_pool.run_until(exit_handler(error));
+                        local_pool.run_until(exit_handler(exit_reason));
                     }
                 }
             }
         }
     }
 
-    async fn run_once(event_handlers: &mut [EventHandler<E>]) -> Result<EventHandlingOutcome, E> {
-        let stream = stream::iter(event_handlers.iter_mut());
+    /// Returns the `ControlFlow` to wait for the next event handler to have
+    /// work.
+    ///
+    /// When every event handler is event driven and none has an event, the
+    /// event loop waits until one is woken, or a handler group is enabled or
+    /// disabled. Otherwise the event loop polls, which `winit` drives using
+    /// `requestAnimationFrame` on WASM.
+    fn control_flow(
+        event_handlers: &mut [EventHandler<E>],
+        handler_groups: &HandlerGroups,
+        waker: &Waker,
+    ) -> ControlFlow {
+        let mut cx = Context::from_waker(waker);
+        let is_groups_changed = handler_groups.poll_changed(&mut cx).is_ready();
+        let is_waiting = !is_groups_changed
+            && !event_handlers.is_empty()
+            && event_handlers.iter_mut().all(|event_handler| {
+                event_handler.is_ended()
+                    || (event_handler.is_event_driven()
+                        && event_handler.poll_ready(&mut cx).is_pending())
+            });
+
+        if is_waiting {
+            ControlFlow::Wait
+        } else {
+            ControlFlow::Poll
+        }
+    }
+
+    async fn run_once(
+        event_handlers: &mut [EventHandler<E>],
+        handler_groups: &HandlerGroups,
+    ) -> Result<EventHandlingOutcome, E> {
+        let stream = stream::iter(event_handlers.iter_mut().filter(|event_handler| {
+            handler_groups.is_event_handler_enabled(event_handler) && !event_handler.is_ended()
+        }));
 
         stream
             .map(Result::<_, E>::Ok)
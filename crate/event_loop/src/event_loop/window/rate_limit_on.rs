@@ -1,40 +1,59 @@
-use core::future::Future;
-use std::{error::Error, time::Duration};
-
-use futures::{
-    executor::LocalPool,
-    stream::{self, StreamExt},
-    TryStreamExt,
+use std::{
+    error::Error,
+    task::{Context, Waker},
 };
-use governor::clock::{Clock, DefaultClock};
+
+use futures::{channel::mpsc::UnboundedReceiver, executor::LocalPool};
 use instant::Instant;
 use winit::{
     event::Event,
     event_loop::{ControlFlow, EventLoopWindowTarget},
 };
 
-use super::ExitHandler;
+use super::loop_waker::LoopWaker;
+use super::{frame_pacing, ExitHandler};
+#[cfg(feature = "input_replay")]
+use crate::input_recorder::InputRecorder;
 use crate::{
-    event_loop::common::{EventHandlersExt, RateLimiter},
-    EventHandler, EventHandlingOutcome, EventLoop,
+    event_loop::common::{EventHandlersExt, Scheduler},
+    EventHandler, EventHandlingOutcome, EventLoop, EventWriter, ExitReason, HandlerGroups,
+    InputEvent, LoopEvent, StateStack, StopHandle,
 };
 
 #[cfg(not(target_arch = "wasm32"))]
-struct EventLoopParams<'f, E> {
+struct EventLoopParams<'f, E, UserEvent> {
     event_handlers: &'f mut [EventHandler<E>],
     exit_handler: &'f mut Option<ExitHandler<E>>,
-    rate_limiters: &'f [Option<RateLimiter>],
-    clock: &'f DefaultClock,
+    stop_handle: StopHandle,
+    handler_groups: HandlerGroups,
+    state_stack: StateStack,
+    user_event_rx: &'f mut UnboundedReceiver<UserEvent>,
+    user_event_writer: EventWriter<UserEvent>,
+    input_event_writer: EventWriter<InputEvent>,
+    #[cfg(feature = "input_replay")]
+    input_recorder: &'f mut Option<InputRecorder>,
+    waker: Waker,
+    scheduler: &'f mut Scheduler,
+    due: &'f mut Vec<usize>,
     local_pool: &'f mut LocalPool,
     should_exit: &'f mut bool,
 }
 
 #[cfg(target_arch = "wasm32")]
-struct EventLoopParams<'f, E> {
+struct EventLoopParams<'f, E, UserEvent> {
     event_handlers: Vec<EventHandler<E>>,
     exit_handler: Option<ExitHandler<E>>,
-    rate_limiters: Vec<Option<RateLimiter>>,
-    clock: DefaultClock,
+    stop_handle: StopHandle,
+    handler_groups: HandlerGroups,
+    state_stack: StateStack,
+    user_event_rx: UnboundedReceiver<UserEvent>,
+    user_event_writer: EventWriter<UserEvent>,
+    input_event_writer: EventWriter<InputEvent>,
+    #[cfg(feature = "input_replay")]
+    input_recorder: Option<InputRecorder>,
+    waker: Waker,
+    scheduler: Scheduler,
+    due: Vec<usize>,
     local_pool: LocalPool,
     should_exit: bool,
     marker: std::marker::PhantomData<&'f E>,
@@ -42,7 +61,7 @@ struct EventLoopParams<'f, E> {
 
 impl<E, UserEvent> EventLoop<E, UserEvent>
 where
-    E: Error + Send + 'static,
+    E: Error + 'static,
     UserEvent: 'static,
 {
     /// Runs the event loop until `Exit` is signalled or an error occurs.
@@ -54,17 +73,37 @@ where
             mut event_handlers,
             mut winit_event_loop,
             mut exit_handler,
+            stop_handle,
+            event_bus,
+            handler_groups,
+            state_stack,
+            introspection: _,
+            user_event_tx: _,
+            mut user_event_rx,
+            #[cfg(feature = "input_replay")]
+            mut input_recorder,
             is_in_main_thread,
         } = self;
-        let rate_limiters = event_handlers.rate_limiters();
+        let display_refresh_rate = frame_pacing::display_refresh_rate(&winit_event_loop);
+        let mut scheduler =
+            Scheduler::new(event_handlers.frame_paced_rate_limiters(display_refresh_rate));
+        let mut due = Vec::with_capacity(event_handlers.len());
+
+        state_stack.apply_transitions();
 
+        let user_event_writer = event_bus.writer::<UserEvent>();
+        let input_event_writer = event_bus.writer::<InputEvent>();
+        let waker = LoopWaker::waker(winit_event_loop.create_proxy());
+        stop_handle.wake_on_stop(waker.clone());
         let mut local_pool = LocalPool::new();
-        let clock = DefaultClock::default();
 
         let event_handlers = &mut event_handlers;
         let exit_handler = &mut exit_handler;
-        let rate_limiters = &rate_limiters;
-        let clock = &clock;
+        let user_event_rx = &mut user_event_rx;
+        #[cfg(feature = "input_replay")]
+        let input_recorder = &mut input_recorder;
+        let scheduler = &mut scheduler;
+        let due = &mut due;
         let local_pool = &mut local_pool;
         let mut should_exit = false;
 
@@ -73,8 +112,17 @@ where
             let event_loop_params = EventLoopParams {
                 event_handlers,
                 exit_handler,
-                rate_limiters,
-                clock,
+                stop_handle: stop_handle.clone(),
+                handler_groups: handler_groups.clone(),
+                state_stack: state_stack.clone(),
+                user_event_rx,
+                user_event_writer: user_event_writer.clone(),
+                input_event_writer: input_event_writer.clone(),
+                #[cfg(feature = "input_replay")]
+                input_recorder,
+                waker: waker.clone(),
+                scheduler,
+                due,
                 local_pool,
                 should_exit,
             };
@@ -94,16 +142,40 @@ where
             event_handlers,
             winit_event_loop,
             exit_handler,
-            is_in_main_thread,
+            stop_handle,
+            event_bus,
+            handler_groups,
+            state_stack,
+            introspection: _,
+            user_event_rx,
+            #[cfg(feature = "input_replay")]
+            input_recorder,
+            ..
         } = self;
 
-        let rate_limiters = event_handlers.rate_limiters();
+        let display_refresh_rate = frame_pacing::display_refresh_rate(&winit_event_loop);
+        let scheduler =
+            Scheduler::new(event_handlers.frame_paced_rate_limiters(display_refresh_rate));
+        let due = Vec::with_capacity(event_handlers.len());
+
+        state_stack.apply_transitions();
 
+        let waker = LoopWaker::waker(winit_event_loop.create_proxy());
+        stop_handle.wake_on_stop(waker.clone());
         let event_loop_params = EventLoopParams {
             event_handlers,
             exit_handler,
-            rate_limiters,
-            clock: DefaultClock::default(),
+            stop_handle,
+            handler_groups,
+            state_stack,
+            user_event_rx,
+            user_event_writer: event_bus.writer::<UserEvent>(),
+            input_event_writer: event_bus.writer::<InputEvent>(),
+            #[cfg(feature = "input_replay")]
+            input_recorder,
+            waker,
+            scheduler,
+            due,
             local_pool: LocalPool::new(),
             should_exit: false,
             marker: std::marker::PhantomData,
@@ -113,146 +185,143 @@ where
     }
 
     fn fn_event_loop<'f>(
-        mut event_loop_params: EventLoopParams<'f, E>,
-    ) -> impl FnMut(Event<UserEvent>, &EventLoopWindowTarget<UserEvent>, &mut ControlFlow) + 'f
+        mut event_loop_params: EventLoopParams<'f, E, UserEvent>,
+    ) -> impl FnMut(Event<LoopEvent>, &EventLoopWindowTarget<LoopEvent>, &mut ControlFlow) + 'f
     {
-        move |_event, _, control_flow| {
+        move |event, _, control_flow| {
             // Run event handlers that are ready.
 
             // We cannot run event handlers in this closure, as it isn't `async`, but we can
             // submit them to a local executor to be run on the main thread.
+            //
+            // Event handlers are run once per `winit` loop iteration. On WASM, polling is
+            // driven by `requestAnimationFrame`, and waiting by `setTimeout`, so unlimited
+            // event handlers run once per animation frame.
 
             let EventLoopParams {
                 ref mut event_handlers,
                 ref mut exit_handler,
-                ref rate_limiters,
-                ref clock,
+                ref stop_handle,
+                ref handler_groups,
+                ref state_stack,
+                ref mut user_event_rx,
+                ref user_event_writer,
+                ref input_event_writer,
+                #[cfg(feature = "input_replay")]
+                ref mut input_recorder,
+                ref waker,
+                ref mut scheduler,
+                ref mut due,
                 ref mut local_pool,
                 ref mut should_exit,
                 ..
             } = event_loop_params;
 
-            if !**should_exit {
-                let event_handlers_task =
-                    Self::event_handler_task(event_handlers, rate_limiters, clock);
-
-                // Run the event handlers
-                let outcome = local_pool.run_until(event_handlers_task);
-                let mut error = None;
-                *control_flow = match outcome {
-                    Ok((event_handling_outcome, duration_to_wait)) => {
-                        match event_handling_outcome {
-                            Ok(EventHandlingOutcome::Continue) => {
-                                match duration_to_wait {
-                                    Some(duration) => {
-                                        // Need to do this, because rate limit instant may be a
-                                        // different type, such as `QuantaInstant`.
-                                        let instant = Instant::now() + duration;
-                                        ControlFlow::WaitUntil(instant)
-                                    }
-                                    None => ControlFlow::Poll,
-                                }
-                            }
-                            Ok(EventHandlingOutcome::Exit) => ControlFlow::Exit,
-                            Err(e) => {
-                                error = Some(e);
-                                ControlFlow::Exit
+            if let Some(input_event) = InputEvent::from_event(&event) {
+                #[cfg(feature = "input_replay")]
+                {
+                    if let Some(input_recorder) = input_recorder.as_mut() {
+                        input_recorder.record_event(input_event);
+                    }
+                }
+                input_event_writer.emit(input_event);
+                return;
+            }
+
+            if !**should_exit && matches!(event, Event::MainEventsCleared) {
+                let exit_reason = if stop_handle.is_stop_requested() {
+                    Some(ExitReason::Stopped(stop_handle.reason()))
+                } else {
+                    #[cfg(not(target_arch = "wasm32"))]
+                    frame_pacing::spin_until_frame(scheduler);
+
+                    Self::receive_user_events(user_event_rx, user_event_writer, waker);
+
+                    Self::unpark(event_handlers, scheduler, waker);
+                    scheduler.take_due(Instant::now(), due, |index| {
+                        handler_groups.is_event_handler_enabled(&event_handlers[index])
+                    });
+                    #[cfg(feature = "input_replay")]
+                    Self::record_tick(input_recorder, due.iter().copied());
+
+                    let event_handlers_task = Self::run_due(event_handlers, scheduler, due);
+
+                    // Run the event handlers
+                    let event_handling_outcome = local_pool.run_until(event_handlers_task);
+                    match event_handling_outcome {
+                        Ok(EventHandlingOutcome::Continue)
+                            if !event_handlers.is_empty() && scheduler.is_empty() =>
+                        {
+                            Some(ExitReason::Exit)
+                        }
+                        Ok(EventHandlingOutcome::Continue) => {
+                            // Schedules event handlers whose groups were enabled during this tick,
+                            // and registers the waker to be woken when a group is next enabled.
+                            state_stack.apply_transitions();
+                            let mut cx = Context::from_waker(waker);
+                            if handler_groups.poll_changed(&mut cx).is_ready() {
+                                scheduler.enable(Instant::now(), |index| {
+                                    handler_groups.is_event_handler_enabled(&event_handlers[index])
+                                });
                             }
+
+                            // Registers the waker with event handlers that were just parked.
+                            Self::unpark(event_handlers, scheduler, waker);
+                            *control_flow = frame_pacing::control_flow(scheduler);
+                            None
                         }
-                    }
-                    Err(e) => {
-                        error = Some(e);
-                        ControlFlow::Exit
+                        Ok(EventHandlingOutcome::Exit) => Some(ExitReason::Exit),
+                        Err(e) => Some(ExitReason::Error(e)),
                     }
                 };
 
-                if *control_flow == ControlFlow::Exit {
+                if let Some(exit_reason) = exit_reason {
+                    *control_flow = ControlFlow::Exit;
                     **should_exit = true;
+                    #[cfg(feature = "input_replay")]
+                    Self::finish_recording(input_recorder);
+                    event_handlers.iter_mut().for_each(EventHandler::on_exit);
                     if let Some(exit_handler) = exit_handler.take() {
-                        local_pool.run_until(exit_handler(error));
+                        local_pool.run_until(exit_handler(exit_reason));
                     }
                 }
             }
         }
     }
 
-    fn event_handler_task<'f>(
-        event_handlers: &'f mut [EventHandler<E>],
-        rate_limiters: &'f [Option<RateLimiter>],
-        clock: &'f DefaultClock,
-    ) -> impl Future<Output = Result<(Result<EventHandlingOutcome, E>, Option<Duration>), E>> + 'f
-    {
-        async move {
-            stream::iter(rate_limiters.iter().zip(event_handlers.iter_mut()))
-                .map(Result::Ok)
-                .try_fold(
-                    (Ok(EventHandlingOutcome::Continue), None),
-                    |(outcome_cumulative, duration_to_wait), (rate_limiter, event_handler)| {
-                        Self::run_event_handler(
-                            clock,
-                            outcome_cumulative,
-                            duration_to_wait,
-                            rate_limiter,
-                            event_handler,
-                        )
-                    },
-                )
-                .await
-        }
+    /// Schedules the parked event handlers that have an event, and registers
+    /// the waker with the others.
+    fn unpark(event_handlers: &mut [EventHandler<E>], scheduler: &mut Scheduler, waker: &Waker) {
+        let mut cx = Context::from_waker(waker);
+        scheduler.unpark(Instant::now(), |index| {
+            event_handlers[index].poll_ready(&mut cx).is_ready()
+        });
     }
 
-    /// Returns a future that
-    fn run_event_handler<'f>(
-        clock: &'f DefaultClock,
-        mut outcome_cumulative: Result<EventHandlingOutcome, E>,
-        mut duration_to_wait: Option<Duration>,
-        rate_limiter: &'f Option<RateLimiter>,
-        event_handler: &'f mut EventHandler<E>,
-    ) -> impl Future<Output = Result<(Result<EventHandlingOutcome, E>, Option<Duration>), E>> + 'f
-    {
-        async move {
-            if let Some(rate_limiter) = rate_limiter {
-                match rate_limiter.check() {
-                    Ok(()) => {
-                        let outcome = event_handler.run().await;
-                        outcome_cumulative = Self::outcome_merge(outcome_cumulative, outcome);
-                        duration_to_wait = match rate_limiter.check() {
-                            Ok(()) => Some(Duration::from_millis(0)),
-                            Err(not_until) => {
-                                let duration_to_wait_rate_limiter =
-                                    not_until.wait_time_from(clock.now());
-                                duration_to_wait
-                                    .map(|duration| {
-                                        std::cmp::min(duration, duration_to_wait_rate_limiter)
-                                    })
-                                    .or_else(|| Some(duration_to_wait_rate_limiter))
-                            }
-                        };
-                    }
-                    Err(not_until) => {
-                        let duration_to_wait_rate_limiter = not_until.wait_time_from(clock.now());
-                        duration_to_wait = duration_to_wait
-                            .map(|duration| std::cmp::min(duration, duration_to_wait_rate_limiter))
-                            .or_else(|| Some(duration_to_wait_rate_limiter));
-                    }
-                }
-            } else {
-                let outcome = event_handler.run().await;
-                outcome_cumulative = Self::outcome_merge(outcome_cumulative, outcome);
-                duration_to_wait = Some(Duration::from_millis(0));
+    /// Runs the event handlers that are due, and schedules their next run.
+    async fn run_due(
+        event_handlers: &mut [EventHandler<E>],
+        scheduler: &mut Scheduler,
+        due: &mut Vec<usize>,
+    ) -> Result<EventHandlingOutcome, E> {
+        let mut outcome_cumulative = EventHandlingOutcome::Continue;
+
+        for index in due.drain(..) {
+            let outcome = event_handlers[index].run().await?;
+            outcome_cumulative = std::cmp::max(outcome_cumulative, outcome);
+
+            if event_handlers[index].is_ended() {
+                // Ended event handlers are not scheduled again.
+                continue;
             }
 
-            Result::<_, E>::Ok((outcome_cumulative, duration_to_wait))
+            if event_handlers[index].is_event_driven() {
+                scheduler.park(index);
+            } else {
+                scheduler.reschedule(index, Instant::now());
+            }
         }
-    }
 
-    fn outcome_merge(
-        base: Result<EventHandlingOutcome, E>,
-        patch: Result<EventHandlingOutcome, E>,
-    ) -> Result<EventHandlingOutcome, E> {
-        match (base, patch) {
-            (Err(e), _) | (Ok(_), Err(e)) => Err(e),
-            (Ok(outcome_base), Ok(outcome_patch)) => Ok(std::cmp::max(outcome_base, outcome_patch)),
-        }
+        Ok(outcome_cumulative)
     }
 }
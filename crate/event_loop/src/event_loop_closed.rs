@@ -0,0 +1,19 @@
+use std::{error::Error, fmt};
+
+/// Error when sending a user event to an event loop that has exited.
+///
+/// The user event that could not be sent is returned.
+///
+/// # Type Parameters
+///
+/// * `UserEvent`: Custom user event type.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct EventLoopClosed<UserEvent>(pub UserEvent);
+
+impl<UserEvent> fmt::Display for EventLoopClosed<UserEvent> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("Tried to send a user event to an event loop that has exited.")
+    }
+}
+
+impl<UserEvent> Error for EventLoopClosed<UserEvent> where UserEvent: fmt::Debug {}
@@ -0,0 +1,399 @@
+use std::{collections::HashMap, error::Error};
+#[cfg(not(target_arch = "wasm32"))]
+use std::{fs, io, path::PathBuf, thread};
+
+use futures::channel::oneshot;
+use serde::Deserialize;
+
+use crate::{ConfigError, EventHandler, HandlerConfig};
+
+/// Event handler settings loaded from a RON or TOML file, keyed by event
+/// handler name.
+///
+/// This allows rate limits, ordering, groups, and error policies to be tuned
+/// without recompiling. Settings override the values set in code on each
+/// `EventHandler`, and event handlers that are not declared keep their values.
+///
+/// ```ron
+/// (
+///     handlers: {
+///         "physics": (rate_limit: Some((fps: Some(60)))),
+///         "render": (order: Some(1)),
+///         "telemetry": (error_policy: Some(Skip)),
+///     },
+/// )
+/// ```
+///
+/// The same configuration in TOML:
+///
+/// ```toml
+/// [handlers.physics]
+/// rate_limit = { fps = 60 }
+///
+/// [handlers.render]
+/// order = 1
+///
+/// [handlers.telemetry]
+/// error_policy = "Skip"
+/// ```
+///
+/// This is applied using `EventLoop::with_config`.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct EventLoopConfig {
+    /// Settings of each event handler, keyed by event handler name.
+    pub handlers: HashMap<String, HandlerConfig>,
+}
+
+impl EventLoopConfig {
+    /// Loads the configuration from the given location.
+    ///
+    /// Natively, the location is a path to a file, which is read on a separate
+    /// thread so that the executor is not blocked. On WASM, the location is a
+    /// URL that the configuration is fetched from, as configuration is loaded
+    /// remotely.
+    ///
+    /// Locations ending in `.toml` are parsed as TOML, and other locations are
+    /// parsed as RON.
+    ///
+    /// # Parameters
+    ///
+    /// * `location`: Path or URL of the configuration.
+    pub async fn load(location: &str) -> Result<Self, ConfigError> {
+        let config_str = Self::read(location).await?;
+
+        if location.ends_with(".toml") {
+            Self::from_toml(location, &config_str)
+        } else {
+            Self::from_ron(location, &config_str)
+        }
+    }
+
+    /// Parses the configuration from a RON string.
+    ///
+    /// # Parameters
+    ///
+    /// * `location`: Path or URL of the configuration, used in errors.
+    /// * `config_ron`: The configuration in RON format.
+    pub fn from_ron(location: &str, config_ron: &str) -> Result<Self, ConfigError> {
+        ron::de::from_str(config_ron).map_err(|error| ConfigError::Parse {
+            location: String::from(location),
+            error,
+        })
+    }
+
+    /// Parses the configuration from a TOML string.
+    ///
+    /// # Parameters
+    ///
+    /// * `location`: Path or URL of the configuration, used in errors.
+    /// * `config_toml`: The configuration in TOML format.
+    pub fn from_toml(location: &str, config_toml: &str) -> Result<Self, ConfigError> {
+        toml::de::from_str(config_toml).map_err(|error| ConfigError::ParseToml {
+            location: String::from(location),
+            error,
+        })
+    }
+
+    /// Applies the settings to the event handlers with matching names, then
+    /// orders the event handlers.
+    ///
+    /// # Parameters
+    ///
+    /// * `event_handlers`: Event handlers of the event loop.
+    /// * `error_reporter`: Function to report errors of event handlers that
+    ///   are configured to skip them.
+    pub(crate) fn apply<E>(
+        &self,
+        event_handlers: &mut [EventHandler<E>],
+        error_reporter: fn(&str, &dyn Error),
+    ) -> Result<(), ConfigError>
+    where
+        E: Error + 'static,
+    {
+        if let Some(name) = self.handlers.keys().find(|name| {
+            !event_handlers
+                .iter()
+                .any(|event_handler| event_handler.name() == name.as_str())
+        }) {
+            return Err(ConfigError::HandlerNotFound { name: name.clone() });
+        }
+
+        event_handlers.iter_mut().try_for_each(|event_handler| {
+            match self.handlers.get(event_handler.name()) {
+                Some(handler_config) => event_handler.apply_config(handler_config, error_reporter),
+                None => Ok(()),
+            }
+        })?;
+
+        // Stable, so event handlers with the same order keep their relative order.
+        event_handlers.sort_by_key(|event_handler| {
+            self.handlers
+                .get(event_handler.name())
+                .and_then(|handler_config| handler_config.order)
+                .unwrap_or(0)
+        });
+
+        Ok(())
+    }
+
+    /// Reads the configuration file on a separate thread.
+    #[cfg(not(target_arch = "wasm32"))]
+    async fn read(location: &str) -> Result<String, ConfigError> {
+        let path = PathBuf::from(location);
+        let (config_tx, config_rx) = oneshot::channel();
+
+        thread::spawn({
+            let path = path.clone();
+            move || {
+                let _ = config_tx.send(fs::read_to_string(path));
+            }
+        });
+
+        #[allow(clippy::io_other_error)] // `io::Error::other` requires Rust 1.74.
+        config_rx
+            .await
+            .unwrap_or_else(|_canceled| {
+                Err(io::Error::new(
+                    io::ErrorKind::Other,
+                    "Configuration reader thread panicked.",
+                ))
+            })
+            .map_err(|error| ConfigError::Io { path, error })
+    }
+
+    /// Fetches the configuration from its URL.
+    #[cfg(target_arch = "wasm32")]
+    async fn read(location: &str) -> Result<String, ConfigError> {
+        use wasm_bindgen::{JsCast, JsValue};
+        use web_sys::Response;
+
+        let fetch_error = |error: String| ConfigError::Fetch {
+            url: String::from(location),
+            error,
+        };
+        let js_error = |error: JsValue| fetch_error(format!("{:?}", error));
+
+        let window = web_sys::window().ok_or_else(|| fetch_error(String::from("No window.")))?;
+        let response = Self::settle(window.fetch_with_str(location))
+            .await
+            .map_err(js_error)?
+            .dyn_into::<Response>()
+            .map_err(js_error)?;
+        if !response.ok() {
+            return Err(fetch_error(format!("HTTP status {}.", response.status())));
+        }
+
+        Self::settle(response.text().map_err(js_error)?)
+            .await
+            .map_err(js_error)?
+            .as_string()
+            .ok_or_else(|| fetch_error(String::from("Response is not text.")))
+    }
+
+    /// Waits for a promise to resolve or reject.
+    ///
+    /// This does not use `wasm-bindgen-futures`, as enabling that dependency
+    /// also enables `EventLoop::spawner`.
+    #[cfg(target_arch = "wasm32")]
+    async fn settle(
+        promise: js_sys::Promise,
+    ) -> Result<wasm_bindgen::JsValue, wasm_bindgen::JsValue> {
+        use std::{cell::RefCell, rc::Rc};
+
+        use wasm_bindgen::{closure::Closure, JsValue};
+
+        let (settled_tx, settled_rx) = oneshot::channel();
+        let settled_tx = Rc::new(RefCell::new(Some(settled_tx)));
+        let settle_with = |wrap: fn(JsValue) -> Result<JsValue, JsValue>| {
+            let settled_tx = Rc::clone(&settled_tx);
+            Closure::wrap(Box::new(move |value: JsValue| {
+                if let Some(settled_tx) = settled_tx.borrow_mut().take() {
+                    let _ = settled_tx.send(wrap(value));
+                }
+            }) as Box<dyn FnMut(JsValue)>)
+        };
+        let on_resolve = settle_with(Ok);
+        let on_reject = settle_with(Err);
+        let _promise = promise.then2(&on_resolve, &on_reject);
+
+        // The closures are kept alive until the promise settles.
+        settled_rx
+            .await
+            .unwrap_or_else(|_canceled| Err(JsValue::from_str("Promise was dropped.")))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, error::Error, fs, path::Path, process};
+
+    use crossbeam_channel::SendError;
+
+    use super::EventLoopConfig;
+    use crate::{ConfigError, ErrorPolicy, EventHandler, EventHandlingOutcome};
+
+    #[test]
+    fn apply_overrides_settings_of_named_event_handlers() -> Result<(), ConfigError> {
+        let config = EventLoopConfig::from_ron(
+            "config.ron",
+            r#"(
+                handlers: {
+                    "render": (order: Some(1)),
+                    "input": (order: Some(-1), groups: ["gameplay"]),
+                    "telemetry": (error_policy: Some(Skip)),
+                },
+            )"#,
+        )?;
+        let mut event_handlers = vec![
+            continuer("render"),
+            continuer("physics"),
+            continuer("telemetry"),
+            continuer("input"),
+        ];
+
+        config.apply(&mut event_handlers, ignore_error)?;
+
+        let names = event_handlers
+            .iter()
+            .map(EventHandler::name)
+            .collect::<Vec<_>>();
+        assert_eq!(vec!["input", "physics", "telemetry", "render"], names);
+        assert_eq!(["gameplay"], event_handlers[0].groups());
+        assert!(matches!(
+            event_handlers[2].error_policy(),
+            ErrorPolicy::Skip(_)
+        ));
+        assert!(matches!(
+            event_handlers[1].error_policy(),
+            ErrorPolicy::Exit
+        ));
+
+        Ok(())
+    }
+
+    #[cfg(feature = "rate_limit")]
+    #[test]
+    fn apply_overrides_rate_limit() -> Result<(), ConfigError> {
+        use crate::RateLimit;
+
+        let config = EventLoopConfig::from_ron(
+            "config.ron",
+            r#"(handlers: { "physics": (rate_limit: Some((fps: Some(60), burst: Some(2)))) })"#,
+        )?;
+        let mut event_handlers = vec![continuer("physics")];
+
+        config.apply(&mut event_handlers, ignore_error)?;
+
+        let rate_limit = RateLimit::fps(60).and_then(|rate_limit| rate_limit.with_burst(2));
+        assert_eq!(rate_limit.ok(), event_handlers[0].rate_limit);
+
+        Ok(())
+    }
+
+    #[test]
+    fn apply_returns_error_when_event_handler_does_not_exist() -> Result<(), ConfigError> {
+        let config = EventLoopConfig::from_ron(
+            "config.ron",
+            r#"(handlers: { "physcis": (order: Some(1)) })"#,
+        )?;
+        let mut event_handlers = vec![continuer("physics")];
+
+        match config.apply(&mut event_handlers, ignore_error) {
+            Err(ConfigError::HandlerNotFound { name }) => assert_eq!("physcis", name),
+            result => panic!("Expected `ConfigError::HandlerNotFound`, got: {:?}", result),
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn from_ron_returns_parse_error_for_unknown_setting() {
+        let result =
+            EventLoopConfig::from_ron("config.ron", r#"(handlers: { "physics": (speed: 2) })"#);
+
+        match result {
+            Err(ConfigError::Parse { location, .. }) => assert_eq!("config.ron", location),
+            result => panic!("Expected `ConfigError::Parse`, got: {:?}", result),
+        }
+    }
+
+    #[test]
+    fn from_toml_parses_same_settings_as_from_ron() -> Result<(), ConfigError> {
+        let config_ron = EventLoopConfig::from_ron(
+            "config.ron",
+            r#"(
+                handlers: {
+                    "input": (order: Some(-1), groups: ["gameplay"]),
+                    "telemetry": (error_policy: Some(Skip)),
+                },
+            )"#,
+        )?;
+        let config_toml = EventLoopConfig::from_toml(
+            "config.toml",
+            r#"
+                [handlers.input]
+                order = -1
+                groups = ["gameplay"]
+
+                [handlers.telemetry]
+                error_policy = "Skip"
+            "#,
+        )?;
+
+        assert_eq!(config_ron, config_toml);
+
+        Ok(())
+    }
+
+    #[test]
+    fn from_toml_returns_parse_error_for_unknown_setting() {
+        let result = EventLoopConfig::from_toml("config.toml", "[handlers.physics]\nspeed = 2");
+
+        match result {
+            Err(ConfigError::ParseToml { location, .. }) => assert_eq!("config.toml", location),
+            result => panic!("Expected `ConfigError::ParseToml`, got: {:?}", result),
+        }
+    }
+
+    #[test]
+    fn load_parses_file_format_from_extension() -> Result<(), ConfigError> {
+        let config_dir = env::temp_dir().join(format!("nginee_config_{}", process::id()));
+        fs::create_dir_all(&config_dir).expect("Failed to create configuration directory.");
+        let config_ron_path = config_dir.join("config.ron");
+        let config_toml_path = config_dir.join("config.toml");
+        fs::write(
+            &config_ron_path,
+            r#"(handlers: { "render": (order: Some(1)) })"#,
+        )
+        .expect("Failed to write RON configuration.");
+        fs::write(&config_toml_path, "[handlers.render]\norder = 1")
+            .expect("Failed to write TOML configuration.");
+
+        let config_ron = smol::run(EventLoopConfig::load(&config_ron_path.to_string_lossy()))?;
+        let config_toml = smol::run(EventLoopConfig::load(&config_toml_path.to_string_lossy()))?;
+        let _ = fs::remove_dir_all(&config_dir);
+
+        assert_eq!(Some(1), config_ron.handlers["render"].order);
+        assert_eq!(config_ron, config_toml);
+
+        Ok(())
+    }
+
+    #[test]
+    fn load_returns_io_error_when_file_does_not_exist() {
+        let result = smol::run(EventLoopConfig::load("does_not_exist.ron"));
+
+        match result {
+            Err(ConfigError::Io { path, .. }) => assert_eq!(Path::new("does_not_exist.ron"), path),
+            result => panic!("Expected `ConfigError::Io`, got: {:?}", result),
+        }
+    }
+
+    fn ignore_error(_name: &str, _error: &dyn Error) {}
+
+    fn continuer(name: &'static str) -> EventHandler<SendError<()>> {
+        EventHandler::<SendError<()>>::new_sync(|| Ok(EventHandlingOutcome::Continue))
+            .with_name(name)
+    }
+}
@@ -0,0 +1,77 @@
+use core::fmt::{self, Debug};
+
+use futures::channel::mpsc::UnboundedSender;
+
+use crate::EventLoopClosed;
+
+/// Sends user events to an `EventLoop` from any thread.
+///
+/// User events are emitted to the event loop's `EventBus`, so event handlers
+/// receive them through an `EventReader<UserEvent>`. Event handlers created
+/// with `EventHandler::from_stream` are woken by them.
+///
+/// Sending a user event wakes the event loop if it is idle, including the
+/// window event loop on WASM.
+///
+/// This is obtained through `EventLoop::create_proxy`, and may be cloned.
+///
+/// # Type Parameters
+///
+/// * `UserEvent`: Custom user event type.
+pub struct EventLoopProxy<UserEvent>
+where
+    UserEvent: 'static,
+{
+    /// Sends user events to the event loop.
+    user_event_tx: UnboundedSender<UserEvent>,
+}
+
+impl<UserEvent> EventLoopProxy<UserEvent>
+where
+    UserEvent: 'static,
+{
+    /// Returns a new `EventLoopProxy`.
+    ///
+    /// # Parameters
+    ///
+    /// * `user_event_tx`: Sends user events to the event loop.
+    pub(crate) fn new(user_event_tx: UnboundedSender<UserEvent>) -> Self {
+        Self { user_event_tx }
+    }
+
+    /// Sends a user event to the event loop, waking it if it is idle.
+    ///
+    /// Returns the user event in an error if the event loop has exited.
+    ///
+    /// # Parameters
+    ///
+    /// * `user_event`: The user event to send.
+    pub fn send_event(&self, user_event: UserEvent) -> Result<(), EventLoopClosed<UserEvent>> {
+        // The event loop registers its waker with the receiver, so sending wakes it.
+        self.user_event_tx
+            .unbounded_send(user_event)
+            .map_err(|error| EventLoopClosed(error.into_inner()))
+    }
+}
+
+// Manual implementation, as deriving it would require `UserEvent: Clone`.
+impl<UserEvent> Clone for EventLoopProxy<UserEvent>
+where
+    UserEvent: 'static,
+{
+    fn clone(&self) -> Self {
+        Self {
+            user_event_tx: self.user_event_tx.clone(),
+        }
+    }
+}
+
+#[cfg_attr(tarpaulin, skip)]
+impl<UserEvent> Debug for EventLoopProxy<UserEvent>
+where
+    UserEvent: 'static,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("EventLoopProxy").finish()
+    }
+}
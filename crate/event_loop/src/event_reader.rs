@@ -0,0 +1,97 @@
+use core::{
+    fmt::{self, Debug},
+    pin::Pin,
+    task::{Context, Poll},
+};
+use std::{cell::RefCell, rc::Rc};
+
+use futures::stream::Stream;
+
+use crate::event_channel::EventChannel;
+
+/// Reads events of type `T` from the `EventBus`.
+///
+/// Each reader has its own cursor, so every reader receives every event,
+/// unless the event was dropped because the channel was full.
+///
+/// This is obtained through `EventBus::reader`.
+///
+/// An `EventReader` is also a `Stream` of events that never ends, so it may be
+/// passed to `EventHandler::from_stream` to run an event handler once per
+/// event.
+///
+/// # Type Parameters
+///
+/// * `T`: Event type.
+pub struct EventReader<T> {
+    /// Channel to read events from.
+    channel: Rc<RefCell<EventChannel<T>>>,
+    /// Sequence number of the next event to read.
+    cursor: u64,
+}
+
+impl<T> EventReader<T> {
+    /// Returns a new `EventReader`, which reads events emitted after this
+    /// call.
+    pub(crate) fn new(channel: Rc<RefCell<EventChannel<T>>>) -> Self {
+        let cursor = channel.borrow().end();
+        Self { channel, cursor }
+    }
+
+    /// Returns the events emitted since the last read, oldest first.
+    ///
+    /// Events are cloned, as other readers may read them too.
+    pub fn read(&mut self) -> Vec<T>
+    where
+        T: Clone,
+    {
+        let channel = self.channel.borrow();
+        let events = channel.events_since(self.cursor).cloned().collect();
+        self.cursor = channel.end();
+
+        events
+    }
+
+    /// Returns whether there are events that have not been read.
+    pub fn has_events(&self) -> bool {
+        let channel = self.channel.borrow();
+        let has_events = channel.events_since(self.cursor).next().is_some();
+
+        has_events
+    }
+}
+
+impl<T> Stream for EventReader<T>
+where
+    T: Clone,
+{
+    type Item = T;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let event_reader = self.get_mut();
+        let mut channel = event_reader.channel.borrow_mut();
+        let sequence = event_reader.cursor.max(channel.first());
+
+        let event = channel.events_since(sequence).next().cloned();
+        match event {
+            Some(event) => {
+                event_reader.cursor = sequence + 1;
+                Poll::Ready(Some(event))
+            }
+            None => {
+                channel.register(cx.waker());
+                Poll::Pending
+            }
+        }
+    }
+}
+
+#[cfg_attr(tarpaulin, skip)]
+impl<T> Debug for EventReader<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("EventReader")
+            .field("channel", &"..")
+            .field("cursor", &self.cursor)
+            .finish()
+    }
+}
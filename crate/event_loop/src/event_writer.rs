@@ -0,0 +1,65 @@
+use core::fmt::{self, Debug};
+use std::{cell::RefCell, rc::Rc};
+
+use crate::event_channel::EventChannel;
+
+/// Emits events of type `T` to the `EventBus`.
+///
+/// This is obtained through `EventBus::writer`.
+///
+/// # Type Parameters
+///
+/// * `T`: Event type.
+pub struct EventWriter<T> {
+    /// Channel to emit events to.
+    channel: Rc<RefCell<EventChannel<T>>>,
+}
+
+impl<T> EventWriter<T> {
+    /// Returns a new `EventWriter`.
+    pub(crate) fn new(channel: Rc<RefCell<EventChannel<T>>>) -> Self {
+        Self { channel }
+    }
+
+    /// Emits an event.
+    ///
+    /// If the channel is full, its oldest event is dropped.
+    ///
+    /// # Parameters
+    ///
+    /// * `event`: The event to emit.
+    pub fn emit(&self, event: T) {
+        self.channel.borrow_mut().emit(event);
+    }
+
+    /// Emits each of the given events.
+    ///
+    /// # Parameters
+    ///
+    /// * `events`: The events to emit.
+    pub fn emit_all<I>(&self, events: I)
+    where
+        I: IntoIterator<Item = T>,
+    {
+        let mut channel = self.channel.borrow_mut();
+        events.into_iter().for_each(|event| channel.emit(event));
+    }
+}
+
+// Manual implementation, as deriving it would require `T: Clone`.
+impl<T> Clone for EventWriter<T> {
+    fn clone(&self) -> Self {
+        Self {
+            channel: self.channel.clone(),
+        }
+    }
+}
+
+#[cfg_attr(tarpaulin, skip)]
+impl<T> Debug for EventWriter<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("EventWriter")
+            .field("channel", &"..")
+            .finish()
+    }
+}
@@ -0,0 +1,14 @@
+/// Why the event loop exited, passed to the exit handler.
+///
+/// # Type Parameters
+///
+/// * `E`: Error type.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ExitReason<E> {
+    /// An event handler returned `EventHandlingOutcome::Exit`.
+    Exit,
+    /// A stop was requested through a `StopHandle`, with an optional reason.
+    Stopped(Option<String>),
+    /// An event handler returned an error.
+    Error(E),
+}
@@ -0,0 +1,75 @@
+use std::{num::NonZeroU32, time::Duration};
+
+/// Paces a render event handler to the display's refresh.
+///
+/// Frame paced event handlers run on a fixed grid of frame boundaries, instead
+/// of as soon as their rate limit allows, so that frames are evenly spaced.
+///
+/// Natively, the event loop sleeps until shortly before each frame boundary,
+/// then spins until the boundary is reached. On WASM, the event loop wakes on
+/// each `requestAnimationFrame` callback while a frame paced event handler is
+/// next due.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FramePacing {
+    /// Runs once per refresh of the primary monitor.
+    ///
+    /// If the refresh rate cannot be determined, such as on WASM, 60 Hz is
+    /// used.
+    DisplayRefresh,
+    /// Runs once per refresh at the given rate in hertz.
+    RefreshRate(NonZeroU32),
+}
+
+impl FramePacing {
+    /// Refresh rate used when the display's refresh rate is unknown.
+    pub const REFRESH_RATE_DEFAULT: u32 = 60;
+
+    /// Returns the duration between frame boundaries.
+    ///
+    /// # Parameters
+    ///
+    /// * `display_refresh_rate`: Refresh rate of the display in hertz, if
+    ///   known.
+    pub fn frame_interval(self, display_refresh_rate: Option<NonZeroU32>) -> Duration {
+        let refresh_rate = match self {
+            FramePacing::DisplayRefresh => display_refresh_rate
+                .map(NonZeroU32::get)
+                .unwrap_or(Self::REFRESH_RATE_DEFAULT),
+            FramePacing::RefreshRate(refresh_rate) => refresh_rate.get(),
+        };
+
+        Duration::from_secs(1) / refresh_rate
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{num::NonZeroU32, time::Duration};
+
+    use crate::FramePacing;
+
+    #[test]
+    fn display_refresh_uses_display_refresh_rate() {
+        assert_eq!(
+            Duration::from_millis(10),
+            FramePacing::DisplayRefresh.frame_interval(NonZeroU32::new(100))
+        );
+    }
+
+    #[test]
+    fn display_refresh_defaults_to_60_hz() {
+        assert_eq!(
+            Duration::from_secs(1) / 60,
+            FramePacing::DisplayRefresh.frame_interval(None)
+        );
+    }
+
+    #[test]
+    fn refresh_rate_ignores_display_refresh_rate() {
+        assert_eq!(
+            Duration::from_millis(20),
+            FramePacing::RefreshRate(NonZeroU32::new(50).unwrap())
+                .frame_interval(NonZeroU32::new(100))
+        );
+    }
+}
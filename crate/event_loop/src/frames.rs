@@ -0,0 +1,85 @@
+use core::{cell::Cell, future::Future, task::Poll};
+use std::{rc::Rc, time::Duration};
+
+use futures::future;
+use futures_timer::Delay;
+
+/// Frame boundaries that a coroutine event handler may await.
+///
+/// A frame is one run of the coroutine's event handler, so frames follow the
+/// event handler's rate limit or frame pacing when one is set.
+///
+/// While the coroutine awaits anything other than a frame count, such as
+/// [`Frames::wait`], the event handler is not run until the awaited future
+/// wakes it.
+#[derive(Clone, Debug, Default)]
+pub struct Frames {
+    /// Number of frames that have elapsed since the coroutine started.
+    frame: Rc<Cell<u64>>,
+    /// Whether the coroutine is waiting for a number of frames to elapse.
+    is_waiting_frames: Rc<Cell<bool>>,
+}
+
+impl Frames {
+    /// Returns a new `Frames`.
+    pub(crate) fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the number of frames that have elapsed since the coroutine
+    /// started.
+    ///
+    /// This is `0` on the first frame.
+    pub fn frame(&self) -> u64 {
+        self.frame.get()
+    }
+
+    /// Waits until the next frame.
+    pub fn next_frame(&self) -> impl Future<Output = ()> {
+        self.wait_frames(1)
+    }
+
+    /// Waits until `count` frames have elapsed.
+    ///
+    /// # Parameters
+    ///
+    /// * `count`: Number of frames to wait for, where `0` does not wait.
+    pub fn wait_frames(&self, count: u64) -> impl Future<Output = ()> {
+        let frame = self.frame.clone();
+        let is_waiting_frames = self.is_waiting_frames.clone();
+        let until = frame.get() + count;
+
+        future::poll_fn(move |_| {
+            if frame.get() >= until {
+                Poll::Ready(())
+            } else {
+                is_waiting_frames.set(true);
+                Poll::Pending
+            }
+        })
+    }
+
+    /// Waits until the first frame after `duration` has elapsed.
+    ///
+    /// The event handler is not run while waiting, and the event loop sleeps
+    /// if no other event handler is due.
+    ///
+    /// # Parameters
+    ///
+    /// * `duration`: Duration to wait for.
+    pub fn wait(&self, duration: Duration) -> impl Future<Output = ()> {
+        Delay::new(duration)
+    }
+
+    /// Returns whether the coroutine is waiting for a number of frames to
+    /// elapse, and so must be run on every frame.
+    pub(crate) fn is_waiting_frames(&self) -> bool {
+        self.is_waiting_frames.get()
+    }
+
+    /// Advances to the next frame.
+    pub(crate) fn advance(&self) {
+        self.frame.set(self.frame.get() + 1);
+        self.is_waiting_frames.set(false);
+    }
+}
@@ -0,0 +1,132 @@
+use core::future::Future;
+use std::{any, error::Error};
+
+#[cfg(feature = "rate_limit")]
+use crate::RateLimit;
+use crate::{EventHandlerResult, HandlerContext};
+
+/// Event handler logic implemented on a type, for handlers that own state.
+///
+/// This is an alternative to writing event handler logic as a capturing
+/// closure. A `Handler` is converted into an `EventHandler` using
+/// `EventHandler::from`.
+pub trait Handler: 'static {
+    /// Error type returned by the handler.
+    type Error: Error + 'static;
+
+    /// Future returned by `run`.
+    ///
+    /// The future is stored in one allocation that is reused across runs, so
+    /// it does not borrow the handler. Handlers whose logic is synchronous run
+    /// it in `run`, and return `futures::future::Ready`.
+    type Future: Future<Output = EventHandlerResult<Self::Error>> + 'static;
+
+    /// Runs the handler logic.
+    ///
+    /// # Parameters
+    ///
+    /// * `context`: Information about this run.
+    fn run(&mut self, context: &HandlerContext) -> Self::Future;
+
+    /// Returns the name of this handler, used when reporting it.
+    ///
+    /// Defaults to the type name of the handler.
+    fn name(&self) -> &str {
+        any::type_name::<Self>()
+    }
+
+    /// Returns the rate to limit this handler's execution.
+    ///
+    /// Defaults to no limit.
+    #[cfg(feature = "rate_limit")]
+    fn rate_limit(&self) -> Option<RateLimit> {
+        None
+    }
+
+    /// Runs before the handler is run for the first time.
+    fn on_start(&mut self) {}
+
+    /// Runs when the event loop exits, if the handler has been started.
+    fn on_exit(&mut self) {}
+}
+
+#[cfg(all(test, not(feature = "window")))]
+mod tests {
+    use std::{cell::RefCell, rc::Rc};
+
+    use crossbeam_channel::SendError;
+    use futures::future::{self, Ready};
+
+    use crate::{
+        EventHandler, EventHandlerResult, EventHandlingOutcome, EventLoop, Handler, HandlerContext,
+    };
+
+    #[test]
+    fn event_loop_runs_handler_with_lifecycle_hooks() -> Result<(), SendError<()>> {
+        let calls = Rc::new(RefCell::new(Vec::new()));
+        let countdown = Countdown {
+            count: 3,
+            calls: calls.clone(),
+        };
+
+        let event_loop = EventLoop::new(vec![EventHandler::from(countdown)]);
+
+        smol::run(event_loop.run())?;
+
+        assert_eq!(
+            vec!["on_start", "run 0", "run 1", "run 2", "on_exit"],
+            *calls.borrow()
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn event_handler_from_handler_uses_handler_name() {
+        let countdown = Countdown {
+            count: 3,
+            calls: Rc::default(),
+        };
+
+        let event_handler = EventHandler::from(countdown);
+
+        assert!(
+            event_handler.name().ends_with("Countdown"),
+            "name: {}",
+            event_handler.name()
+        );
+    }
+
+    #[derive(Debug)]
+    struct Countdown {
+        count: u32,
+        calls: Rc<RefCell<Vec<String>>>,
+    }
+
+    impl Handler for Countdown {
+        type Error = SendError<()>;
+
+        type Future = Ready<EventHandlerResult<Self::Error>>;
+
+        fn run(&mut self, context: &HandlerContext) -> Self::Future {
+            self.calls
+                .borrow_mut()
+                .push(format!("run {}", context.tick()));
+
+            self.count -= 1;
+            if self.count > 0 {
+                future::ready(Ok(EventHandlingOutcome::Continue))
+            } else {
+                future::ready(Ok(EventHandlingOutcome::Exit))
+            }
+        }
+
+        fn on_start(&mut self) {
+            self.calls.borrow_mut().push(String::from("on_start"));
+        }
+
+        fn on_exit(&mut self) {
+            self.calls.borrow_mut().push(String::from("on_exit"));
+        }
+    }
+}
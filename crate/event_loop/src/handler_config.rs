@@ -0,0 +1,36 @@
+use serde::Deserialize;
+
+use crate::ErrorPolicyConfig;
+#[cfg(feature = "rate_limit")]
+use crate::RateLimitConfig;
+
+/// Settings of an event handler, declared in an `EventLoopConfig`.
+///
+/// Each setting overrides the value set in code on the `EventHandler`. Unset
+/// settings keep the value set in code.
+///
+/// ```ron
+/// (
+///     order: Some(-1),
+///     groups: ["gameplay"],
+///     rate_limit: Some((fps: Some(60))),
+///     error_policy: Some(Skip),
+/// )
+/// ```
+#[derive(Clone, Debug, Default, Deserialize, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct HandlerConfig {
+    /// Position of the event handler relative to others, defaults to `0`.
+    ///
+    /// Event handlers run in ascending order. Event handlers with the same
+    /// order keep the order they were added in.
+    pub order: Option<i32>,
+    /// Groups to add the event handler to, in addition to its existing groups.
+    pub groups: Vec<String>,
+    /// Rate to limit the event handler's execution.
+    #[cfg(feature = "rate_limit")]
+    #[cfg_attr(feature = "docs", doc(cfg(rate_limit)))]
+    pub rate_limit: Option<RateLimitConfig>,
+    /// What to do when the event handler returns an error.
+    pub error_policy: Option<ErrorPolicyConfig>,
+}
@@ -0,0 +1,25 @@
+/// Information passed to a `Handler` each time it is run.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct HandlerContext {
+    /// Number of times the handler has been run before this run.
+    tick: u64,
+}
+
+impl HandlerContext {
+    /// Returns a new `HandlerContext`.
+    pub(crate) fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the number of times the handler has been run before this run.
+    ///
+    /// This is `0` on the first run.
+    pub fn tick(&self) -> u64 {
+        self.tick
+    }
+
+    /// Advances the context to the next run.
+    pub(crate) fn advance(&mut self) {
+        self.tick += 1;
+    }
+}
@@ -0,0 +1,196 @@
+use std::{
+    borrow::Cow,
+    collections::HashSet,
+    error::Error,
+    sync::{Arc, Mutex, MutexGuard, PoisonError},
+    task::{Context, Poll, Waker},
+};
+
+use crate::EventHandler;
+
+/// Enables and disables groups of event handlers at runtime.
+///
+/// Event handlers are tagged with groups using `EventHandler::with_group`. An
+/// event handler is not run while any of its groups is disabled, and does not
+/// consume its rate limit while disabled. All groups are enabled by default.
+///
+/// This is obtained through `EventLoop::handler_groups`, and may be cloned
+/// into event handlers or sent to other threads, for example to disable
+/// gameplay event handlers when the game is paused. Enabling or disabling a
+/// group wakes the event loop, so that it reschedules event handlers even
+/// while every one is disabled.
+#[derive(Clone, Debug, Default)]
+pub struct HandlerGroups {
+    /// Disabled groups, and the waker of the event loop.
+    state: Arc<Mutex<HandlerGroupsState>>,
+}
+
+/// State shared between `HandlerGroups` clones.
+#[derive(Debug, Default)]
+struct HandlerGroupsState {
+    /// Names of the disabled groups.
+    disabled: HashSet<Cow<'static, str>>,
+    /// Whether a group was enabled or disabled since the event loop last
+    /// polled for changes.
+    is_changed: bool,
+    /// Wakes the event loop when a group is enabled or disabled.
+    waker: Option<Waker>,
+}
+
+impl HandlerGroups {
+    /// Returns a new `HandlerGroups`, with all groups enabled.
+    pub(crate) fn new() -> Self {
+        Self::default()
+    }
+
+    /// Disables the event handlers in the given group.
+    ///
+    /// # Parameters
+    ///
+    /// * `group`: Name of the group to disable.
+    pub fn disable<G>(&self, group: G)
+    where
+        G: Into<Cow<'static, str>>,
+    {
+        let is_changed = lock(&self.state).disabled.insert(group.into());
+        if is_changed {
+            self.wake();
+        }
+    }
+
+    /// Enables the event handlers in the given group.
+    ///
+    /// # Parameters
+    ///
+    /// * `group`: Name of the group to enable.
+    pub fn enable(&self, group: &str) {
+        let is_changed = lock(&self.state).disabled.remove(group);
+        if is_changed {
+            self.wake();
+        }
+    }
+
+    /// Returns whether the given group is enabled.
+    ///
+    /// # Parameters
+    ///
+    /// * `group`: Name of the group.
+    pub fn is_enabled(&self, group: &str) -> bool {
+        !lock(&self.state).disabled.contains(group)
+    }
+
+    /// Returns whether none of the event handler's groups are disabled.
+    pub(crate) fn is_event_handler_enabled<E>(&self, event_handler: &EventHandler<E>) -> bool
+    where
+        E: Error + 'static,
+    {
+        self.are_enabled(event_handler.groups())
+    }
+
+    /// Returns whether none of the given groups are disabled.
+    pub(crate) fn are_enabled(&self, groups: &[Cow<'static, str>]) -> bool {
+        let state = lock(&self.state);
+        let disabled = &state.disabled;
+        disabled.is_empty() || groups.iter().all(|group| !disabled.contains(group))
+    }
+
+    /// Returns `Ready` if a group was enabled or disabled since this was last
+    /// polled, otherwise registers the waker to be woken when one is.
+    pub(crate) fn poll_changed(&self, cx: &mut Context<'_>) -> Poll<()> {
+        let mut state = lock(&self.state);
+        if state.is_changed {
+            state.is_changed = false;
+            Poll::Ready(())
+        } else {
+            state.waker = Some(cx.waker().clone());
+            Poll::Pending
+        }
+    }
+
+    /// Records that a group was enabled or disabled, and wakes the event loop.
+    fn wake(&self) {
+        let waker = {
+            let mut state = lock(&self.state);
+            state.is_changed = true;
+            state.waker.take()
+        };
+
+        if let Some(waker) = waker {
+            waker.wake();
+        }
+    }
+}
+
+/// Locks the mutex, recovering the value if another thread panicked.
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        sync::{
+            atomic::{AtomicBool, Ordering},
+            Arc,
+        },
+        task::{Context, Poll},
+    };
+
+    use crossbeam_channel::SendError;
+    use futures::task::{self, ArcWake};
+
+    use super::HandlerGroups;
+    use crate::{EventHandler, EventHandlingOutcome};
+
+    #[test]
+    fn event_handler_is_disabled_when_any_group_is_disabled() {
+        let handler_groups = HandlerGroups::new();
+        let event_handler =
+            EventHandler::<SendError<()>>::new_sync(|| Ok(EventHandlingOutcome::Continue))
+                .with_group("gameplay")
+                .with_group("physics");
+
+        handler_groups.disable("physics");
+
+        assert!(handler_groups.is_enabled("gameplay"));
+        assert!(!handler_groups.is_enabled("physics"));
+        assert!(!handler_groups.is_event_handler_enabled(&event_handler));
+
+        handler_groups.enable("physics");
+
+        assert!(handler_groups.is_event_handler_enabled(&event_handler));
+    }
+
+    #[test]
+    fn enable_and_disable_wake_registered_waker() {
+        let handler_groups = HandlerGroups::new();
+        let flag_waker = Arc::new(FlagWaker::default());
+        let waker = task::waker(flag_waker.clone());
+        let mut cx = Context::from_waker(&waker);
+
+        assert_eq!(Poll::Pending, handler_groups.poll_changed(&mut cx));
+
+        handler_groups.disable("gameplay");
+
+        assert!(flag_waker.woken.swap(false, Ordering::SeqCst));
+        assert_eq!(Poll::Ready(()), handler_groups.poll_changed(&mut cx));
+        assert_eq!(Poll::Pending, handler_groups.poll_changed(&mut cx));
+
+        handler_groups.enable("gameplay");
+
+        assert!(flag_waker.woken.load(Ordering::SeqCst));
+        assert_eq!(Poll::Ready(()), handler_groups.poll_changed(&mut cx));
+    }
+
+    /// Records whether it has been woken.
+    #[derive(Debug, Default)]
+    struct FlagWaker {
+        woken: AtomicBool,
+    }
+
+    impl ArcWake for FlagWaker {
+        fn wake_by_ref(arc_self: &Arc<Self>) {
+            arc_self.woken.store(true, Ordering::SeqCst);
+        }
+    }
+}
@@ -0,0 +1,24 @@
+use std::borrow::Cow;
+
+use instant::Instant;
+
+#[cfg(feature = "rate_limit")]
+use crate::RateLimit;
+
+/// Snapshot of an event handler's settings and status, from `Introspection`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct HandlerInfo {
+    /// Name of the event handler.
+    pub name: Cow<'static, str>,
+    /// Groups the event handler belongs to.
+    pub groups: Vec<Cow<'static, str>>,
+    /// Rate to limit the event handler's execution.
+    #[cfg(feature = "rate_limit")]
+    #[cfg_attr(feature = "docs", doc(cfg(rate_limit)))]
+    pub rate_limit: Option<RateLimit>,
+    /// Whether none of the event handler's groups are disabled.
+    pub is_enabled: bool,
+    /// When the event handler's logic last started running, `None` if it has
+    /// not run.
+    pub last_run: Option<Instant>,
+}
@@ -0,0 +1,44 @@
+use std::{fmt, time::Duration};
+
+/// An event handler ran for longer than its watchdog timeout.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HandlerTimeout {
+    /// Name of the event handler.
+    pub name: String,
+    /// How long the event handler had been running when it was detected.
+    pub elapsed: Duration,
+    /// The watchdog timeout the event handler exceeded.
+    pub timeout: Duration,
+}
+
+impl fmt::Display for HandlerTimeout {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Event handler `{}` exceeded its `{:?}` timeout, running for `{:?}`.",
+            self.name, self.timeout, self.elapsed
+        )
+    }
+}
+
+impl std::error::Error for HandlerTimeout {}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::HandlerTimeout;
+
+    #[test]
+    fn display_includes_name_and_durations() {
+        assert_eq!(
+            "Event handler `physics` exceeded its `10ms` timeout, running for `12ms`.",
+            HandlerTimeout {
+                name: String::from("physics"),
+                elapsed: Duration::from_millis(12),
+                timeout: Duration::from_millis(10),
+            }
+            .to_string()
+        )
+    }
+}
@@ -0,0 +1,474 @@
+use core::fmt::{self, Debug};
+use std::{
+    borrow::Cow,
+    env, fs,
+    path::{Path, PathBuf},
+    process,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    thread,
+    time::{Duration, SystemTime},
+};
+
+use futures::future::{self, Ready};
+use libloading::Library;
+
+use crate::{EventHandlerResult, EventHandlingOutcome, Handler, HandlerContext, HotReloadError};
+
+/// Function exported by a hot reloaded library, run each time the handler
+/// runs.
+///
+/// # Type Parameters
+///
+/// * `S`: State preserved across reloads.
+pub type HotReloadFn<S> = fn(&mut S) -> EventHandlingOutcome;
+
+/// Default interval between checks of the library's modification time.
+const WATCH_INTERVAL_DEFAULT: Duration = Duration::from_millis(500);
+
+/// Handler whose logic lives in a dynamic library, which is reloaded when the
+/// library file changes.
+///
+/// The library is loaded when the handler first runs. A background thread
+/// then watches the library's modification time, so ticks do not access the
+/// file system. When the library changes, it is reloaded before the next run.
+/// Each library is copied to the temporary directory and loaded from there,
+/// so that the original file may be rebuilt while the game is running. The
+/// previous library is unloaded once the new one is loaded.
+///
+/// The state is owned by this handler, so it is preserved across reloads. If
+/// a reload fails, the error is passed to the reporter and the previous
+/// library is kept; only a failure to load the library the first time is
+/// returned as an error.
+///
+/// The library is typically a `cdylib` crate that exports the function:
+///
+/// ```rust,ignore
+/// #[no_mangle]
+/// pub fn tick(state: &mut GameState) -> EventHandlingOutcome {
+///     state.frame += 1;
+///     EventHandlingOutcome::Continue
+/// }
+/// ```
+///
+/// This is converted into an `EventHandler` using `EventHandler::from`.
+///
+/// # Type Parameters
+///
+/// * `S`: State preserved across reloads.
+pub struct HotReload<S> {
+    /// Path to the library that is watched.
+    library_path: PathBuf,
+    /// Name of the `HotReloadFn` exported by the library.
+    symbol: Cow<'static, str>,
+    /// State passed to the library's function.
+    ///
+    /// This is declared before `loaded` so that it is dropped before the
+    /// library is unloaded.
+    state: S,
+    /// The currently loaded library.
+    loaded: Option<LoadedLibrary<S>>,
+    /// Records changes to the library, `None` until the handler first runs.
+    watch: Option<Arc<LibraryWatch>>,
+    /// Interval between checks of the library's modification time.
+    watch_interval: Duration,
+    /// Number of times the library has been loaded, used to name its copies.
+    load_count: u64,
+    /// Reports libraries that fail to reload.
+    reporter: fn(&HotReloadError),
+}
+
+impl<S> HotReload<S>
+where
+    S: 'static,
+{
+    /// Returns a new `HotReload` handler.
+    ///
+    /// # Parameters
+    ///
+    /// * `library_path`: Path to the dynamic library to load.
+    /// * `symbol`: Name of the `HotReloadFn` exported by the library.
+    /// * `state`: State passed to the library's function.
+    /// * `reporter`: Function to report libraries that fail to reload, e.g. to
+    ///   a logger.
+    ///
+    /// # Safety
+    ///
+    /// The symbol must be a `HotReloadFn<S>`, and the library must be built
+    /// with the same compiler and definition of `S` as the game. The state
+    /// must not hold values created by the library that refer to its code,
+    /// such as function pointers or trait objects, as these are invalidated
+    /// when the library is reloaded.
+    pub unsafe fn new<P, N>(
+        library_path: P,
+        symbol: N,
+        state: S,
+        reporter: fn(&HotReloadError),
+    ) -> Self
+    where
+        P: Into<PathBuf>,
+        N: Into<Cow<'static, str>>,
+    {
+        Self {
+            library_path: library_path.into(),
+            symbol: symbol.into(),
+            state,
+            loaded: None,
+            watch: None,
+            watch_interval: WATCH_INTERVAL_DEFAULT,
+            load_count: 0,
+            reporter,
+        }
+    }
+
+    /// Sets the interval between checks of the library's modification time,
+    /// which defaults to 500 milliseconds.
+    ///
+    /// This takes effect if set before the handler first runs.
+    ///
+    /// # Parameters
+    ///
+    /// * `watch_interval`: Interval between checks.
+    pub fn with_watch_interval(mut self, watch_interval: Duration) -> Self {
+        self.watch_interval = watch_interval;
+        self
+    }
+
+    /// Returns the path to the library that is watched.
+    pub fn library_path(&self) -> &Path {
+        &self.library_path
+    }
+
+    /// Returns the state passed to the library's function.
+    pub fn state(&self) -> &S {
+        &self.state
+    }
+
+    /// Loads the library if it has changed, reporting failures when a previous
+    /// library is still loaded.
+    fn reload_if_modified(&mut self) -> Result<(), HotReloadError> {
+        match self.load_if_modified() {
+            Err(error) if self.loaded.is_some() => {
+                (self.reporter)(&error);
+                Ok(())
+            }
+            result => result,
+        }
+    }
+
+    /// Loads the library on the first run, when the watcher has recorded that
+    /// it changed, or when it has not loaded successfully.
+    fn load_if_modified(&mut self) -> Result<(), HotReloadError> {
+        if self.watch.is_none() {
+            let modified =
+                modified_time(&self.library_path).map_err(|error| HotReloadError::Io {
+                    path: self.library_path.clone(),
+                    error,
+                })?;
+
+            self.watch = Some(LibraryWatch::spawn(
+                self.library_path.clone(),
+                modified,
+                self.watch_interval,
+            ));
+        }
+
+        let is_modified = match self.watch.as_ref() {
+            Some(library_watch) => library_watch.is_modified.swap(false, Ordering::AcqRel),
+            None => false,
+        };
+        if is_modified || self.loaded.is_none() {
+            self.loaded = Some(self.load()?);
+        }
+
+        Ok(())
+    }
+
+    /// Copies the library to the temporary directory and loads it.
+    fn load(&mut self) -> Result<LoadedLibrary<S>, HotReloadError> {
+        let file_name = self
+            .library_path
+            .file_name()
+            .map(|file_name| file_name.to_string_lossy())
+            .unwrap_or_default();
+        let copy_path = env::temp_dir().join(format!(
+            "nginee-{}-{}-{}",
+            process::id(),
+            self.load_count,
+            file_name
+        ));
+        self.load_count += 1;
+
+        fs::copy(&self.library_path, &copy_path).map_err(|error| HotReloadError::Io {
+            path: self.library_path.clone(),
+            error,
+        })?;
+
+        let load_error = |error| HotReloadError::Load {
+            path: self.library_path.clone(),
+            error,
+        };
+        let library = Library::new(&copy_path).map_err(|error| {
+            let _ = fs::remove_file(&copy_path);
+            load_error(error)
+        })?;
+
+        // Safety: The caller of `HotReload::new` guarantees the symbol's type.
+        let hot_reload_fn =
+            unsafe { library.get::<HotReloadFn<S>>(self.symbol.as_bytes()) }.map(|symbol| *symbol);
+
+        match hot_reload_fn {
+            Ok(hot_reload_fn) => Ok(LoadedLibrary {
+                hot_reload_fn,
+                library: Some(library),
+                copy_path,
+            }),
+            Err(error) => {
+                drop(library);
+                let _ = fs::remove_file(&copy_path);
+                Err(load_error(error))
+            }
+        }
+    }
+}
+
+impl<S> Handler for HotReload<S>
+where
+    S: 'static,
+{
+    type Error = HotReloadError;
+    type Future = Ready<EventHandlerResult<Self::Error>>;
+
+    fn run(&mut self, _context: &HandlerContext) -> Self::Future {
+        let event_handler_result = self.reload_if_modified().map(|()| {
+            let hot_reload_fn = self
+                .loaded
+                .as_ref()
+                .map(|loaded| loaded.hot_reload_fn)
+                .expect("Library is loaded when reloading succeeds.");
+
+            hot_reload_fn(&mut self.state)
+        });
+
+        future::ready(event_handler_result)
+    }
+
+    fn name(&self) -> &str {
+        &self.symbol
+    }
+}
+
+#[cfg_attr(tarpaulin, skip)]
+impl<S> Debug for HotReload<S>
+where
+    S: Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("HotReload")
+            .field("library_path", &self.library_path)
+            .field("symbol", &self.symbol)
+            .field("state", &self.state)
+            .field(
+                "loaded",
+                &self.loaded.as_ref().map(|loaded| &loaded.copy_path),
+            )
+            .field("watch", &self.watch)
+            .field("watch_interval", &self.watch_interval)
+            .field("load_count", &self.load_count)
+            .field("reporter", &"..")
+            .finish()
+    }
+}
+
+/// A library copy that is loaded, which is removed when unloaded.
+struct LoadedLibrary<S> {
+    /// Function exported by the library.
+    hot_reload_fn: HotReloadFn<S>,
+    /// The loaded library, `None` once unloaded.
+    library: Option<Library>,
+    /// Path to the copy of the library.
+    copy_path: PathBuf,
+}
+
+impl<S> Drop for LoadedLibrary<S> {
+    fn drop(&mut self) {
+        // The library must be unloaded before its file is removed on Windows.
+        drop(self.library.take());
+        let _ = fs::remove_file(&self.copy_path);
+    }
+}
+
+/// Records whether the library changed, which is set by a watcher thread.
+#[derive(Debug, Default)]
+struct LibraryWatch {
+    /// Whether the library changed since it was last loaded.
+    is_modified: AtomicBool,
+}
+
+impl LibraryWatch {
+    /// Spawns a thread that records when the library's modification time
+    /// changes, which exits once the returned `LibraryWatch` is dropped.
+    ///
+    /// # Parameters
+    ///
+    /// * `library_path`: Path to the library to watch.
+    /// * `modified`: Modification time of the library when it was loaded.
+    /// * `watch_interval`: Interval between checks of the modification time.
+    fn spawn(library_path: PathBuf, modified: SystemTime, watch_interval: Duration) -> Arc<Self> {
+        let library_watch = Arc::new(Self::default());
+        let library_watch_weak = Arc::downgrade(&library_watch);
+
+        thread::spawn(move || {
+            let mut modified_last = modified;
+            loop {
+                thread::sleep(watch_interval);
+                let library_watch = match library_watch_weak.upgrade() {
+                    Some(library_watch) => library_watch,
+                    None => break,
+                };
+
+                // The library may be missing while it is rebuilt, so it is checked again later.
+                if let Ok(modified) = modified_time(&library_path) {
+                    if modified != modified_last {
+                        modified_last = modified;
+                        library_watch.is_modified.store(true, Ordering::Release);
+                    }
+                }
+            }
+        });
+
+        library_watch
+    }
+}
+
+/// Returns the modification time of the file at the given path.
+fn modified_time(path: &Path) -> Result<SystemTime, std::io::Error> {
+    fs::metadata(path).and_then(|metadata| metadata.modified())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        env::{self, consts},
+        ffi::OsString,
+        fs, io,
+        path::Path,
+        process::{self, Command},
+        sync::atomic::Ordering,
+        thread,
+        time::{Duration, Instant},
+    };
+
+    use super::HotReload;
+    use crate::{EventHandlingOutcome, Handler, HandlerContext, HotReloadError};
+
+    #[test]
+    fn run_reloads_library_when_rebuilt_and_preserves_state() -> Result<(), io::Error> {
+        let library_dir = env::temp_dir().join(format!("nginee_hot_reload_{}", process::id()));
+        fs::create_dir_all(&library_dir)?;
+        let library_path =
+            library_dir.join(format!("{}tick{}", consts::DLL_PREFIX, consts::DLL_SUFFIX));
+
+        build_library(&library_dir, &library_path, 1)?;
+        let mut hot_reload = unsafe {
+            HotReload::new(library_path.clone(), "tick", 0u32, |error| {
+                panic!("Failed to reload library: {}", error)
+            })
+        }
+        .with_watch_interval(Duration::from_millis(5));
+
+        let outcome = smol::run(hot_reload.run(&HandlerContext::new()));
+        assert_eq!(
+            EventHandlingOutcome::Continue,
+            outcome.expect("Library loads.")
+        );
+        assert_eq!(1, *hot_reload.state());
+
+        build_library(&library_dir, &library_path, 10)?;
+        let start = Instant::now();
+        let library_watch = hot_reload.watch.clone().expect("Library is watched.");
+        while !library_watch.is_modified.load(Ordering::Acquire) {
+            assert!(
+                start.elapsed() < Duration::from_secs(5),
+                "Library change was not detected."
+            );
+            thread::sleep(Duration::from_millis(5));
+        }
+
+        let outcome = smol::run(hot_reload.run(&HandlerContext::new()));
+        assert_eq!(
+            EventHandlingOutcome::Continue,
+            outcome.expect("Library reloads.")
+        );
+        assert_eq!(11, *hot_reload.state());
+
+        drop(hot_reload);
+        fs::remove_dir_all(&library_dir)
+    }
+
+    #[test]
+    fn run_returns_io_error_when_library_does_not_exist() {
+        let library_path = env::temp_dir().join("nginee_hot_reload_missing.so");
+        let mut hot_reload =
+            unsafe { HotReload::new(library_path.clone(), "tick", 0u32, |_error| {}) };
+
+        let result = smol::run(hot_reload.run(&HandlerContext::new()));
+
+        match result {
+            Err(HotReloadError::Io { path, error }) => {
+                assert_eq!(library_path, path);
+                assert_eq!(io::ErrorKind::NotFound, error.kind());
+            }
+            result => panic!("Expected `HotReloadError::Io`, got: {:?}", result),
+        }
+    }
+
+    #[test]
+    fn run_returns_load_error_when_file_is_not_a_library() -> Result<(), io::Error> {
+        let library_path = env::temp_dir().join("nginee_hot_reload_not_a_library.so");
+        fs::write(&library_path, b"not a library")?;
+        let mut hot_reload =
+            unsafe { HotReload::new(library_path.clone(), "tick", 0u32, |_error| {}) };
+
+        let result = smol::run(hot_reload.run(&HandlerContext::new()));
+        fs::remove_file(&library_path)?;
+
+        match result {
+            Err(HotReloadError::Load { path, .. }) => assert_eq!(library_path, path),
+            result => panic!("Expected `HotReloadError::Load`, got: {:?}", result),
+        }
+
+        Ok(())
+    }
+
+    /// Builds a library whose `tick` function adds `increment` to the state,
+    /// and moves it to `library_path`.
+    fn build_library(library_dir: &Path, library_path: &Path, increment: u32) -> io::Result<()> {
+        // The enum mirrors `EventHandlingOutcome`, which the library does not depend on.
+        let source = format!(
+            "pub enum EventHandlingOutcome {{ Continue, Exit }}\n\
+             #[no_mangle]\n\
+             pub fn tick(state: &mut u32) -> EventHandlingOutcome {{\n\
+                 *state += {};\n\
+                 EventHandlingOutcome::Continue\n\
+             }}\n",
+            increment
+        );
+        let source_path = library_dir.join(format!("tick_{}.rs", increment));
+        let build_path = library_dir.join(format!("tick_{}", increment));
+        fs::write(&source_path, source)?;
+
+        let rustc = env::var_os("RUSTC").unwrap_or_else(|| OsString::from("rustc"));
+        let status = Command::new(rustc)
+            .args(["--edition", "2018", "--crate-type", "cdylib", "-o"])
+            .arg(&build_path)
+            .arg(&source_path)
+            .status()?;
+        assert!(status.success(), "Failed to build library.");
+
+        // Moved into place, so the library is never read while partially written.
+        fs::rename(&build_path, library_path)
+    }
+}
@@ -0,0 +1,38 @@
+use std::{fmt, io, path::PathBuf};
+
+/// Errors when loading a hot reloaded library.
+#[derive(Debug)]
+pub enum HotReloadError {
+    /// Failed to read or copy the library file.
+    Io {
+        /// Path to the library.
+        path: PathBuf,
+        /// The underlying error.
+        error: io::Error,
+    },
+    /// Failed to load the library, or to find the symbol in it.
+    Load {
+        /// Path to the library.
+        path: PathBuf,
+        /// The underlying error.
+        error: libloading::Error,
+    },
+}
+
+impl fmt::Display for HotReloadError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Io { path, .. } => write!(f, "Failed to read library `{}`.", path.display()),
+            Self::Load { path, .. } => write!(f, "Failed to load library `{}`.", path.display()),
+        }
+    }
+}
+
+impl std::error::Error for HotReloadError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io { error, .. } => Some(error),
+            Self::Load { error, .. } => Some(error),
+        }
+    }
+}
@@ -0,0 +1,26 @@
+use std::time::Duration;
+
+/// What the headless event loop does when no event handler is due.
+///
+/// Regardless of the strategy, the event loop always yields to the executor
+/// between ticks, so that other tasks on the same executor may run.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum IdleStrategy {
+    /// Yields to the executor, then checks again immediately.
+    ///
+    /// This has the lowest latency, at the cost of using a full CPU core.
+    Yield,
+    /// Sleeps for the given duration, then checks again.
+    ///
+    /// Event handlers may run up to this duration late.
+    Sleep(Duration),
+    /// Sleeps until the next event handler is due.
+    ParkUntilDeadline,
+}
+
+#[allow(clippy::derivable_impls)] // `#[default]` on enum variants requires Rust 1.62.
+impl Default for IdleStrategy {
+    fn default() -> Self {
+        IdleStrategy::ParkUntilDeadline
+    }
+}
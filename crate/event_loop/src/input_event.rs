@@ -0,0 +1,215 @@
+#[cfg(feature = "input_replay")]
+use serde::{Deserialize, Serialize};
+use winit::{
+    dpi::{PhysicalPosition, PhysicalSize},
+    event::{
+        AxisId, ButtonId, DeviceEvent, ElementState, Event, KeyboardInput, ModifiersState,
+        MouseButton, MouseScrollDelta, TouchPhase, WindowEvent,
+    },
+};
+
+/// Input received by the window or from devices, emitted to the `EventBus`.
+///
+/// Event handlers read these through an `EventReader<InputEvent>`. Window and
+/// device IDs are not kept, so that input may be recorded and replayed.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "input_replay", derive(Deserialize, Serialize))]
+pub enum InputEvent {
+    /// The window was resized to the given size.
+    Resized(PhysicalSize<u32>),
+    /// The window has been requested to close.
+    CloseRequested,
+    /// The window gained (`true`) or lost (`false`) focus.
+    Focused(bool),
+    /// The window received a unicode character.
+    ReceivedCharacter(char),
+    /// A key was pressed or released.
+    KeyboardInput(KeyboardInput),
+    /// The keyboard modifiers have changed.
+    ModifiersChanged(ModifiersState),
+    /// The cursor moved to the given position, relative to the top left corner
+    /// of the window.
+    CursorMoved(PhysicalPosition<f64>),
+    /// The cursor entered the window.
+    CursorEntered,
+    /// The cursor left the window.
+    CursorLeft,
+    /// The mouse wheel or touchpad was scrolled.
+    MouseWheel {
+        /// Amount scrolled.
+        delta: MouseScrollDelta,
+        /// Phase of a touchpad scroll.
+        phase: TouchPhase,
+    },
+    /// A mouse button was pressed or released.
+    MouseInput {
+        /// Whether the button was pressed or released.
+        state: ElementState,
+        /// The mouse button.
+        button: MouseButton,
+    },
+    /// The window's scale factor changed.
+    ScaleFactorChanged {
+        /// The new scale factor.
+        scale_factor: f64,
+        /// The window size `winit` suggested for the new scale factor.
+        new_inner_size: PhysicalSize<u32>,
+    },
+    /// A pointing device moved, in unfiltered device units.
+    DeviceMouseMotion {
+        /// Change in position.
+        delta: (f64, f64),
+    },
+    /// A device's scroll wheel was scrolled.
+    DeviceMouseWheel {
+        /// Amount scrolled.
+        delta: MouseScrollDelta,
+    },
+    /// A device's analog axis moved.
+    DeviceMotion {
+        /// The axis that moved.
+        axis: AxisId,
+        /// The axis' value.
+        value: f64,
+    },
+    /// A device's button was pressed or released.
+    DeviceButton {
+        /// The button.
+        button: ButtonId,
+        /// Whether the button was pressed or released.
+        state: ElementState,
+    },
+    /// A device's key was pressed or released.
+    DeviceKey(KeyboardInput),
+    /// A device sent a unicode character.
+    DeviceText {
+        /// The character.
+        codepoint: char,
+    },
+}
+
+impl InputEvent {
+    /// Returns the `InputEvent` for a `winit` event, if it is input.
+    ///
+    /// # Parameters
+    ///
+    /// * `event`: The event to convert.
+    pub fn from_event<T>(event: &Event<'_, T>) -> Option<Self> {
+        match event {
+            Event::WindowEvent { event, .. } => Self::from_window_event(event),
+            Event::DeviceEvent { event, .. } => Self::from_device_event(event),
+            _ => None,
+        }
+    }
+
+    /// Returns the `InputEvent` for a `winit` window event, if it is input.
+    ///
+    /// # Parameters
+    ///
+    /// * `window_event`: The window event to convert.
+    pub fn from_window_event(window_event: &WindowEvent<'_>) -> Option<Self> {
+        let input_event = match window_event {
+            WindowEvent::Resized(size) => Self::Resized(*size),
+            WindowEvent::CloseRequested => Self::CloseRequested,
+            WindowEvent::Focused(focused) => Self::Focused(*focused),
+            WindowEvent::ReceivedCharacter(c) => Self::ReceivedCharacter(*c),
+            WindowEvent::KeyboardInput { input, .. } => Self::KeyboardInput(*input),
+            WindowEvent::ModifiersChanged(modifiers) => Self::ModifiersChanged(*modifiers),
+            WindowEvent::CursorMoved { position, .. } => Self::CursorMoved(*position),
+            WindowEvent::CursorEntered { .. } => Self::CursorEntered,
+            WindowEvent::CursorLeft { .. } => Self::CursorLeft,
+            WindowEvent::MouseWheel { delta, phase, .. } => Self::MouseWheel {
+                delta: *delta,
+                phase: *phase,
+            },
+            WindowEvent::MouseInput { state, button, .. } => Self::MouseInput {
+                state: *state,
+                button: *button,
+            },
+            WindowEvent::ScaleFactorChanged {
+                scale_factor,
+                new_inner_size,
+            } => Self::ScaleFactorChanged {
+                scale_factor: *scale_factor,
+                new_inner_size: **new_inner_size,
+            },
+            _ => return None,
+        };
+
+        Some(input_event)
+    }
+
+    /// Returns the `InputEvent` for a `winit` device event, if it is input.
+    ///
+    /// Devices being added or removed are not input, as device IDs are not
+    /// kept.
+    ///
+    /// # Parameters
+    ///
+    /// * `device_event`: The device event to convert.
+    pub fn from_device_event(device_event: &DeviceEvent) -> Option<Self> {
+        let input_event = match device_event {
+            DeviceEvent::MouseMotion { delta } => Self::DeviceMouseMotion { delta: *delta },
+            DeviceEvent::MouseWheel { delta } => Self::DeviceMouseWheel { delta: *delta },
+            DeviceEvent::Motion { axis, value } => Self::DeviceMotion {
+                axis: *axis,
+                value: *value,
+            },
+            DeviceEvent::Button { button, state } => Self::DeviceButton {
+                button: *button,
+                state: *state,
+            },
+            DeviceEvent::Key(input) => Self::DeviceKey(*input),
+            DeviceEvent::Text { codepoint } => Self::DeviceText {
+                codepoint: *codepoint,
+            },
+            DeviceEvent::Added | DeviceEvent::Removed => return None,
+        };
+
+        Some(input_event)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use winit::{
+        dpi::PhysicalSize,
+        event::{DeviceEvent, ElementState, WindowEvent},
+    };
+
+    use super::InputEvent;
+
+    #[test]
+    fn from_window_event_keeps_scale_factor_change() {
+        let mut new_inner_size = PhysicalSize::new(1600, 1200);
+        let window_event = WindowEvent::ScaleFactorChanged {
+            scale_factor: 2.0,
+            new_inner_size: &mut new_inner_size,
+        };
+
+        assert_eq!(
+            Some(InputEvent::ScaleFactorChanged {
+                scale_factor: 2.0,
+                new_inner_size: PhysicalSize::new(1600, 1200),
+            }),
+            InputEvent::from_window_event(&window_event)
+        );
+    }
+
+    #[test]
+    fn from_device_event_keeps_input_without_device_ids() {
+        let device_event = DeviceEvent::Button {
+            button: 3,
+            state: ElementState::Pressed,
+        };
+
+        assert_eq!(
+            Some(InputEvent::DeviceButton {
+                button: 3,
+                state: ElementState::Pressed,
+            }),
+            InputEvent::from_device_event(&device_event)
+        );
+        assert_eq!(None, InputEvent::from_device_event(&DeviceEvent::Added));
+    }
+}
@@ -0,0 +1,83 @@
+use std::{
+    fs::File,
+    io::{self, BufWriter, Write},
+    mem,
+    path::Path,
+};
+
+use instant::Instant;
+
+use crate::{InputEvent, RecordedTick};
+
+/// Writes input and tick timestamps to an input recording file.
+///
+/// Each tick is written as one line of JSON, so that a recording that is cut
+/// short can still be replayed.
+#[derive(Debug)]
+pub(crate) struct InputRecorder {
+    /// Writes to the recording file.
+    writer: BufWriter<File>,
+    /// When the first tick was recorded.
+    start: Option<Instant>,
+    /// Input received since the last tick.
+    events: Vec<InputEvent>,
+    /// Reports failures to write the recording.
+    reporter: fn(&io::Error),
+}
+
+impl InputRecorder {
+    /// Returns an `InputRecorder` that writes to the given file, replacing it
+    /// if it exists.
+    ///
+    /// # Parameters
+    ///
+    /// * `path`: Path to the recording file.
+    /// * `reporter`: Function to report failures to write the recording.
+    pub fn new(path: &Path, reporter: fn(&io::Error)) -> io::Result<Self> {
+        let writer = BufWriter::new(File::create(path)?);
+
+        Ok(Self {
+            writer,
+            start: None,
+            events: Vec::new(),
+            reporter,
+        })
+    }
+
+    /// Returns the function that reports failures to write the recording.
+    pub fn reporter(&self) -> fn(&io::Error) {
+        self.reporter
+    }
+
+    /// Records input to be written with the next tick.
+    pub fn record_event(&mut self, input_event: InputEvent) {
+        self.events.push(input_event);
+    }
+
+    /// Writes the input received since the last tick, the tick's time, and
+    /// the event handlers that run in it.
+    ///
+    /// # Parameters
+    ///
+    /// * `now`: When the tick is run.
+    /// * `event_handlers`: Indices of the event handlers that run in the tick.
+    pub fn record_tick<I>(&mut self, now: Instant, event_handlers: I) -> io::Result<()>
+    where
+        I: IntoIterator<Item = usize>,
+    {
+        let start = *self.start.get_or_insert(now);
+        let recorded_tick = RecordedTick {
+            elapsed: now - start,
+            events: mem::take(&mut self.events),
+            event_handlers: event_handlers.into_iter().collect(),
+        };
+
+        serde_json::to_writer(&mut self.writer, &recorded_tick)?;
+        self.writer.write_all(b"\n")
+    }
+
+    /// Writes any buffered ticks to the file.
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+}
@@ -0,0 +1,135 @@
+use std::{fs, path::Path};
+
+use crate::{InputReplayError, RecordedTick};
+
+/// Input and tick timestamps recorded from a window event loop.
+///
+/// Recordings are written by `EventLoop::with_input_recording`, and replayed
+/// through event handlers by `InputReplay`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct InputRecording {
+    /// Recorded ticks, in the order they were run.
+    ticks: Vec<RecordedTick>,
+}
+
+impl InputRecording {
+    /// Returns a new `InputRecording` of the given ticks.
+    ///
+    /// This allows tests to replay input without a recording file.
+    ///
+    /// # Parameters
+    ///
+    /// * `ticks`: Recorded ticks, in the order they were run.
+    pub fn new(ticks: Vec<RecordedTick>) -> Self {
+        Self { ticks }
+    }
+
+    /// Reads an input recording from a file.
+    ///
+    /// # Parameters
+    ///
+    /// * `path`: Path to the recording file.
+    pub fn load<P>(path: P) -> Result<Self, InputReplayError>
+    where
+        P: AsRef<Path>,
+    {
+        let path = path.as_ref();
+        let contents = fs::read_to_string(path).map_err(|error| InputReplayError::Io {
+            path: path.to_path_buf(),
+            error,
+        })?;
+
+        let ticks = contents
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(index, line)| {
+                serde_json::from_str(line).map_err(|error| InputReplayError::Parse {
+                    path: path.to_path_buf(),
+                    line: index + 1,
+                    error,
+                })
+            })
+            .collect::<Result<Vec<RecordedTick>, InputReplayError>>()?;
+
+        Ok(Self { ticks })
+    }
+
+    /// Returns the recorded ticks, in the order they were run.
+    pub fn ticks(&self) -> &[RecordedTick] {
+        &self.ticks
+    }
+
+    /// Returns the recorded ticks, in the order they were run.
+    pub(crate) fn into_ticks(self) -> Vec<RecordedTick> {
+        self.ticks
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, fs, time::Duration};
+
+    use instant::Instant;
+    use winit::event::{ElementState, MouseButton};
+
+    use super::InputRecording;
+    use crate::{input_recorder::InputRecorder, InputEvent, InputReplayError, RecordedTick};
+
+    #[test]
+    fn load_reads_ticks_written_by_recorder() -> Result<(), Box<dyn std::error::Error>> {
+        let path = env::temp_dir().join("nginee_input_recording_round_trip.jsonl");
+        let mouse_input = InputEvent::MouseInput {
+            state: ElementState::Pressed,
+            button: MouseButton::Left,
+        };
+
+        let start = Instant::now();
+        let mut input_recorder = InputRecorder::new(&path, |_error| {})?;
+        input_recorder.record_tick(start, vec![0, 1])?;
+        input_recorder.record_event(mouse_input);
+        input_recorder.record_tick(start + Duration::from_millis(16), vec![1])?;
+        input_recorder.flush()?;
+
+        let input_recording = InputRecording::load(&path)?;
+        fs::remove_file(&path)?;
+
+        assert_eq!(
+            &[
+                RecordedTick {
+                    elapsed: Duration::from_millis(0),
+                    events: Vec::new(),
+                    event_handlers: vec![0, 1],
+                },
+                RecordedTick {
+                    elapsed: Duration::from_millis(16),
+                    events: vec![mouse_input],
+                    event_handlers: vec![1],
+                },
+            ],
+            input_recording.ticks()
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn load_returns_parse_error_with_line_number() -> Result<(), Box<dyn std::error::Error>> {
+        let path = env::temp_dir().join("nginee_input_recording_invalid.jsonl");
+        fs::write(
+            &path,
+            "{\"elapsed\":{\"secs\":0,\"nanos\":0},\"events\":[],\"event_handlers\":[]}\nnot \
+             json\n",
+        )?;
+
+        let result = InputRecording::load(&path);
+        fs::remove_file(&path)?;
+
+        match result {
+            Err(InputReplayError::Parse { line, .. }) => assert_eq!(2, line),
+            result => panic!("Expected `InputReplayError::Parse`, got: {:?}", result),
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,288 @@
+use std::error::Error;
+
+use futures_timer::Delay;
+use instant::Instant;
+
+use crate::{
+    EventBus, EventHandler, EventHandlingOutcome, ExitReason, HandlerGroups, InputEvent,
+    InputRecording, State, StateStack, StopHandle,
+};
+
+/// Replays an input recording through event handlers, without a window.
+///
+/// Each recorded tick is run at the same time since the start as it was
+/// recorded. Its input is emitted to the `EventBus` as `InputEvent`s, then the
+/// event handlers that ran in the recorded tick are run in the same order.
+/// Rate limits and handler groups are not applied, as the recorded event
+/// handlers already reflect them, so the event handlers must be added in the
+/// same order as in the recorded event loop. Recorded indices that do not
+/// refer to an event handler are skipped.
+///
+/// This allows bug reports to be reproduced, including in tests, by building
+/// the game's event handlers from this replay's `EventBus` instead of the
+/// event loop's.
+///
+/// # Type Parameters
+///
+/// * `E`: Error type.
+#[derive(Debug)]
+pub struct InputReplay<E> {
+    /// The recording to replay.
+    input_recording: InputRecording,
+    /// `EventHandler`s to run for each recorded tick.
+    event_handlers: Vec<EventHandler<E>>,
+    /// Requests the replay to stop.
+    stop_handle: StopHandle,
+    /// Channels between event handlers.
+    event_bus: EventBus,
+    /// Groups of event handlers that are disabled.
+    handler_groups: HandlerGroups,
+    /// Stack of game states, whose event handlers run while current.
+    state_stack: StateStack,
+}
+
+impl<E> InputReplay<E>
+where
+    E: Error,
+{
+    /// Returns a new `InputReplay`.
+    ///
+    /// # Parameters
+    ///
+    /// * `input_recording`: The recording to replay.
+    /// * `event_handlers`: The logic to run for each recorded tick.
+    pub fn new(input_recording: InputRecording, event_handlers: Vec<EventHandler<E>>) -> Self {
+        let handler_groups = HandlerGroups::new();
+        let state_stack = StateStack::new(handler_groups.clone());
+
+        Self {
+            input_recording,
+            event_handlers,
+            stop_handle: StopHandle::new(),
+            event_bus: EventBus::new(),
+            handler_groups,
+            state_stack,
+        }
+    }
+
+    /// Adds an event handler to run after the existing event handlers.
+    ///
+    /// # Parameters
+    ///
+    /// * `event_handler`: The event handler to add.
+    pub fn with_event_handler(mut self, event_handler: EventHandler<E>) -> Self {
+        self.event_handlers.push(event_handler);
+        self
+    }
+
+    /// Adds a state, whose event handlers run while it is the current state.
+    ///
+    /// # Parameters
+    ///
+    /// * `state`: The state to add.
+    pub fn with_state(mut self, state: State<E>) -> Self
+    where
+        E: 'static,
+    {
+        self.state_stack.add_state(state, &mut self.event_handlers);
+        self
+    }
+
+    /// Returns a handle to request the replay to stop.
+    pub fn stop_handle(&self) -> StopHandle {
+        self.stop_handle.clone()
+    }
+
+    /// Returns the event bus, which the recorded input is emitted to.
+    pub fn event_bus(&self) -> EventBus {
+        self.event_bus.clone()
+    }
+
+    /// Returns the handle to enable and disable groups of event handlers.
+    pub fn handler_groups(&self) -> HandlerGroups {
+        self.handler_groups.clone()
+    }
+
+    /// Returns the stack of game states, which event handlers use to
+    /// transition between states.
+    pub fn state_stack(&self) -> StateStack {
+        self.state_stack.clone()
+    }
+}
+
+impl<E> InputReplay<E>
+where
+    E: Error + 'static,
+{
+    /// Runs the recorded ticks until `Exit` is signalled or an error occurs.
+    ///
+    /// When every recorded tick has run, this returns
+    /// `ExitReason::Stopped(None)`.
+    pub async fn run(mut self) -> ExitReason<E> {
+        let exit_reason = self.run_until_exit().await;
+        self.event_handlers
+            .iter_mut()
+            .for_each(EventHandler::on_exit);
+
+        exit_reason
+    }
+
+    async fn run_until_exit(&mut self) -> ExitReason<E> {
+        let input_event_writer = self.event_bus.writer::<InputEvent>();
+        let recorded_ticks = std::mem::take(&mut self.input_recording).into_ticks();
+        let start = Instant::now();
+
+        self.state_stack.apply_transitions();
+
+        for recorded_tick in recorded_ticks {
+            if self.stop_handle.is_stop_requested() {
+                return ExitReason::Stopped(self.stop_handle.reason());
+            }
+
+            let deadline = start + recorded_tick.elapsed;
+            let now = Instant::now();
+            if deadline > now {
+                Delay::new(deadline - now).await;
+            }
+
+            input_event_writer.emit_all(recorded_tick.events);
+
+            match self.run_once(&recorded_tick.event_handlers).await {
+                Ok(EventHandlingOutcome::Continue) => {
+                    self.state_stack.apply_transitions();
+                }
+                Ok(EventHandlingOutcome::Exit) => return ExitReason::Exit,
+                Err(e) => return ExitReason::Error(e),
+            }
+        }
+
+        ExitReason::Stopped(None)
+    }
+
+    /// Runs the event handlers that ran in a recorded tick.
+    ///
+    /// # Parameters
+    ///
+    /// * `event_handler_indices`: Indices of the event handlers to run.
+    async fn run_once(
+        &mut self,
+        event_handler_indices: &[usize],
+    ) -> Result<EventHandlingOutcome, E> {
+        let mut outcome_cumulative = EventHandlingOutcome::Continue;
+
+        for &index in event_handler_indices {
+            let event_handler = match self.event_handlers.get_mut(index) {
+                Some(event_handler) => event_handler,
+                None => continue,
+            };
+
+            let outcome = event_handler.run().await?;
+            outcome_cumulative = std::cmp::max(outcome_cumulative, outcome);
+        }
+
+        Ok(outcome_cumulative)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::RefCell, rc::Rc, time::Duration};
+
+    use crossbeam_channel::SendError;
+    use winit::event::{ElementState, MouseButton};
+
+    use super::InputReplay;
+    use crate::{
+        EventHandler, EventHandlingOutcome, ExitReason, InputEvent, InputRecording, RecordedTick,
+    };
+
+    #[test]
+    fn run_emits_recorded_input_before_each_tick() {
+        let mouse_input = InputEvent::MouseInput {
+            state: ElementState::Pressed,
+            button: MouseButton::Left,
+        };
+        let input_recording = InputRecording::new(vec![
+            RecordedTick {
+                elapsed: Duration::from_millis(0),
+                events: vec![InputEvent::Focused(true)],
+                event_handlers: vec![0],
+            },
+            RecordedTick {
+                elapsed: Duration::from_millis(2),
+                events: Vec::new(),
+                event_handlers: vec![0],
+            },
+            RecordedTick {
+                elapsed: Duration::from_millis(4),
+                events: vec![mouse_input, InputEvent::CursorLeft],
+                event_handlers: vec![0],
+            },
+        ]);
+        let input_replay = InputReplay::new(input_recording, Vec::new());
+
+        let ticks = Rc::new(RefCell::new(Vec::new()));
+        let event_handler = {
+            let ticks = ticks.clone();
+            let mut input_event_reader = input_replay.event_bus().reader::<InputEvent>();
+            EventHandler::<SendError<()>>::new_sync(move || {
+                ticks.borrow_mut().push(input_event_reader.read());
+                Ok(EventHandlingOutcome::Continue)
+            })
+        };
+
+        let exit_reason = smol::run(input_replay.with_event_handler(event_handler).run());
+
+        assert_eq!(ExitReason::Stopped(None), exit_reason);
+        assert_eq!(
+            vec![
+                vec![InputEvent::Focused(true)],
+                Vec::new(),
+                vec![mouse_input, InputEvent::CursorLeft],
+            ],
+            *ticks.borrow()
+        );
+    }
+
+    #[test]
+    fn run_runs_event_handlers_that_ran_in_each_recorded_tick() {
+        let input_recording = InputRecording::new(vec![
+            RecordedTick {
+                elapsed: Duration::from_millis(0),
+                events: Vec::new(),
+                event_handlers: vec![0, 1],
+            },
+            RecordedTick {
+                elapsed: Duration::from_millis(2),
+                events: Vec::new(),
+                event_handlers: vec![1],
+            },
+            RecordedTick {
+                elapsed: Duration::from_millis(4),
+                events: Vec::new(),
+                event_handlers: vec![0, 2],
+            },
+        ]);
+
+        let runs = Rc::new(RefCell::new(Vec::new()));
+        let event_handler = |name: &'static str| {
+            let runs = runs.clone();
+            EventHandler::<SendError<()>>::new_sync(move || {
+                runs.borrow_mut().push(name);
+                Ok(EventHandlingOutcome::Continue)
+            })
+        };
+        let input_replay = InputReplay::new(
+            input_recording,
+            vec![event_handler("physics"), event_handler("render")],
+        );
+
+        let exit_reason = smol::run(input_replay.run());
+
+        assert_eq!(ExitReason::Stopped(None), exit_reason);
+        assert_eq!(
+            vec!["physics", "render", "render", "physics"],
+            *runs.borrow()
+        );
+    }
+}
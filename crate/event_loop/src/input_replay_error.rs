@@ -0,0 +1,45 @@
+use std::{fmt, io, path::PathBuf};
+
+/// Errors when reading an input recording.
+#[derive(Debug)]
+pub enum InputReplayError {
+    /// Failed to read the recording file.
+    Io {
+        /// Path to the recording.
+        path: PathBuf,
+        /// The underlying error.
+        error: io::Error,
+    },
+    /// A line of the recording is not a valid `RecordedTick`.
+    Parse {
+        /// Path to the recording.
+        path: PathBuf,
+        /// Line number of the invalid tick, starting from `1`.
+        line: usize,
+        /// The underlying error.
+        error: serde_json::Error,
+    },
+}
+
+impl fmt::Display for InputReplayError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Io { path, .. } => write!(f, "Failed to read recording `{}`.", path.display()),
+            Self::Parse { path, line, .. } => write!(
+                f,
+                "Failed to parse line {} of recording `{}`.",
+                line,
+                path.display()
+            ),
+        }
+    }
+}
+
+impl std::error::Error for InputReplayError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io { error, .. } => Some(error),
+            Self::Parse { error, .. } => Some(error),
+        }
+    }
+}
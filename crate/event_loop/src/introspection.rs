@@ -0,0 +1,167 @@
+use std::{
+    borrow::Cow,
+    error::Error,
+    sync::{Arc, Mutex, MutexGuard, PoisonError},
+};
+
+use instant::Instant;
+
+#[cfg(feature = "rate_limit")]
+use crate::RateLimit;
+use crate::{EventHandler, HandlerGroups, HandlerInfo};
+
+/// Lists the event handlers of a running event loop.
+///
+/// This is obtained through `EventLoop::introspection`, and may be cloned into
+/// event handlers or sent to other threads, for example to display a debug
+/// overlay, or to log which event handlers have stopped running.
+///
+/// The event handlers are listed in the order they run, and include those
+/// added after the `Introspection` is obtained.
+#[derive(Clone, Debug)]
+pub struct Introspection {
+    /// Event handlers of the event loop.
+    handlers: Arc<Mutex<Vec<RegisteredHandler>>>,
+    /// Groups of event handlers that are disabled.
+    handler_groups: HandlerGroups,
+}
+
+/// Settings of an event handler, recorded when it is added to the event loop.
+#[derive(Debug)]
+struct RegisteredHandler {
+    /// Name of the event handler.
+    name: Cow<'static, str>,
+    /// Groups the event handler belongs to.
+    groups: Vec<Cow<'static, str>>,
+    /// Rate to limit the event handler's execution.
+    #[cfg(feature = "rate_limit")]
+    rate_limit: Option<RateLimit>,
+    /// When the event handler last ran, updated by the event handler.
+    last_run: Arc<Mutex<Option<Instant>>>,
+}
+
+impl Introspection {
+    /// Returns a new `Introspection`.
+    ///
+    /// # Parameters
+    ///
+    /// * `handler_groups`: Groups of event handlers that are disabled.
+    pub(crate) fn new(handler_groups: HandlerGroups) -> Self {
+        Self {
+            handlers: Arc::default(),
+            handler_groups,
+        }
+    }
+
+    /// Returns a snapshot of each event handler's settings and status.
+    pub fn handlers(&self) -> Vec<HandlerInfo> {
+        lock(&self.handlers)
+            .iter()
+            .map(|registered_handler| HandlerInfo {
+                name: registered_handler.name.clone(),
+                groups: registered_handler.groups.clone(),
+                #[cfg(feature = "rate_limit")]
+                rate_limit: registered_handler.rate_limit,
+                is_enabled: self.handler_groups.are_enabled(&registered_handler.groups),
+                last_run: *lock(&registered_handler.last_run),
+            })
+            .collect()
+    }
+
+    /// Returns a snapshot of the named event handler's settings and status.
+    ///
+    /// If multiple event handlers have the same name, the first is returned.
+    ///
+    /// # Parameters
+    ///
+    /// * `name`: Name of the event handler.
+    pub fn handler(&self, name: &str) -> Option<HandlerInfo> {
+        self.handlers()
+            .into_iter()
+            .find(|handler_info| handler_info.name == name)
+    }
+
+    /// Records the event loop's event handlers, replacing any previously
+    /// recorded.
+    ///
+    /// This is called whenever the event loop's event handlers are added or
+    /// configured.
+    ///
+    /// # Parameters
+    ///
+    /// * `event_handlers`: Event handlers of the event loop.
+    pub(crate) fn register<E>(&self, event_handlers: &[EventHandler<E>])
+    where
+        E: Error + 'static,
+    {
+        let registered_handlers = event_handlers
+            .iter()
+            .map(|event_handler| RegisteredHandler {
+                name: Cow::Owned(String::from(event_handler.name())),
+                groups: event_handler.groups().to_vec(),
+                #[cfg(feature = "rate_limit")]
+                rate_limit: event_handler.rate_limit,
+                last_run: event_handler.last_run_handle(),
+            })
+            .collect::<Vec<_>>();
+
+        *lock(&self.handlers) = registered_handlers;
+    }
+}
+
+/// Locks the mutex, recovering the value if another thread panicked.
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::thread;
+
+    use crossbeam_channel::SendError;
+
+    use super::Introspection;
+    use crate::{EventHandler, EventHandlingOutcome, HandlerGroups};
+
+    #[test]
+    fn handlers_reports_enabled_status_and_last_run() {
+        let handler_groups = HandlerGroups::new();
+        let introspection = Introspection::new(handler_groups.clone());
+        let mut event_handlers = vec![
+            continuer().with_name("physics").with_group("gameplay"),
+            continuer().with_name("render"),
+        ];
+
+        introspection.register(&event_handlers);
+        handler_groups.disable("gameplay");
+        let _ = smol::run(event_handlers[1].run());
+
+        let physics = introspection
+            .handler("physics")
+            .expect("physics is registered");
+        let render = introspection
+            .handler("render")
+            .expect("render is registered");
+        assert!(!physics.is_enabled);
+        assert_eq!(None, physics.last_run);
+        assert!(render.is_enabled);
+        assert_eq!(event_handlers[1].last_run(), render.last_run);
+        assert!(render.last_run.is_some());
+    }
+
+    #[test]
+    fn handlers_is_readable_from_another_thread() {
+        let introspection = Introspection::new(HandlerGroups::new());
+        introspection.register(&[continuer().with_name("physics")]);
+
+        let handler_infos = thread::spawn(move || introspection.handlers())
+            .join()
+            .expect("Introspection thread panicked.");
+
+        assert_eq!("physics", handler_infos[0].name);
+    }
+
+    fn continuer() -> EventHandler<SendError<()>> {
+        EventHandler::<SendError<()>>::new_sync(|| Ok(EventHandlingOutcome::Continue))
+    }
+}
@@ -19,21 +19,153 @@ macro_rules! cfg_rate_limit {
     }
 }
 
+#[doc(hidden)]
+macro_rules! cfg_spawner {
+    ($($item:item)*) => {
+        $(
+            #[cfg(any(
+                all(
+                    not(target_arch = "wasm32"),
+                    any(feature = "async-std", feature = "smol", feature = "tokio")
+                ),
+                all(target_arch = "wasm32", feature = "wasm-bindgen-futures")
+            ))]
+            #[cfg_attr(
+                feature = "docs",
+                doc(cfg(any(
+                    feature = "async-std",
+                    feature = "smol",
+                    feature = "tokio",
+                    feature = "wasm-bindgen-futures"
+                )))
+            )]
+            $item
+        )*
+    }
+}
+
 cfg_rate_limit! {
     mod error;
+    #[cfg(feature = "config")]
+    mod rate_limit_config;
+    #[cfg(feature = "window")]
+    mod frame_pacing;
+    #[cfg(not(feature = "window"))]
+    mod idle_strategy;
+    mod quota;
     mod rate_limit;
 
     pub use crate::error::Error;
+    #[cfg(feature = "window")]
+    pub use crate::frame_pacing::FramePacing;
+    #[cfg(not(feature = "window"))]
+    pub use crate::idle_strategy::IdleStrategy;
+    pub use crate::quota::Quota;
     pub use crate::rate_limit::RateLimit;
-    pub use governor::Quota;
+    #[cfg(feature = "config")]
+    #[cfg_attr(feature = "docs", doc(cfg(config)))]
+    pub use crate::rate_limit_config::RateLimitConfig;
 }
 
+mod boxed_error;
+#[cfg(feature = "config")]
+mod config_error;
+mod error_policy;
+#[cfg(feature = "config")]
+mod error_policy_config;
+mod event_bus;
+mod event_channel;
 mod event_handler;
 mod event_handler_result;
 mod event_handling_outcome;
 mod event_loop;
+mod event_loop_closed;
+#[cfg(feature = "config")]
+mod event_loop_config;
+mod event_loop_proxy;
+mod event_reader;
+mod event_writer;
+#[cfg(feature = "window")]
+mod exit_reason;
+mod frames;
+mod handler;
+#[cfg(feature = "config")]
+mod handler_config;
+mod handler_context;
+mod handler_groups;
+mod handler_info;
+#[cfg(feature = "watchdog")]
+mod handler_timeout;
+#[cfg(all(feature = "hot_reload", not(target_arch = "wasm32")))]
+mod hot_reload;
+#[cfg(all(feature = "hot_reload", not(target_arch = "wasm32")))]
+mod hot_reload_error;
+#[cfg(feature = "window")]
+mod input_event;
+#[cfg(feature = "input_replay")]
+mod input_recorder;
+#[cfg(feature = "input_replay")]
+mod input_recording;
+#[cfg(feature = "input_replay")]
+mod input_replay;
+#[cfg(feature = "input_replay")]
+mod input_replay_error;
+mod introspection;
+#[cfg(feature = "window")]
+mod loop_event;
+#[cfg(feature = "input_replay")]
+mod recorded_tick;
+#[cfg(all(feature = "signal", unix))]
+mod signal_guard;
+mod state;
+mod state_hooks;
+mod state_stack;
+mod stop_handle;
+#[cfg(feature = "watchdog")]
+mod watchdog;
+#[cfg(feature = "watchdog")]
+mod watchdog_policy;
 
+cfg_spawner! {
+    mod spawner;
+
+    pub use crate::spawner::Spawner;
+}
+
+#[cfg(all(feature = "signal", unix))]
+#[cfg_attr(feature = "docs", doc(cfg(all(signal, unix))))]
+pub use crate::signal_guard::SignalGuard;
 pub use crate::{
+    boxed_error::BoxedError, error_policy::ErrorPolicy, event_bus::EventBus,
     event_handler::EventHandler, event_handler_result::EventHandlerResult,
     event_handling_outcome::EventHandlingOutcome, event_loop::EventLoop,
+    event_loop_closed::EventLoopClosed, event_loop_proxy::EventLoopProxy,
+    event_reader::EventReader, event_writer::EventWriter, frames::Frames, handler::Handler,
+    handler_context::HandlerContext, handler_groups::HandlerGroups, handler_info::HandlerInfo,
+    introspection::Introspection, state::State, state_stack::StateStack, stop_handle::StopHandle,
+};
+#[cfg(feature = "config")]
+#[cfg_attr(feature = "docs", doc(cfg(config)))]
+pub use crate::{
+    config_error::ConfigError, error_policy_config::ErrorPolicyConfig,
+    event_loop_config::EventLoopConfig, handler_config::HandlerConfig,
+};
+#[cfg(feature = "window")]
+pub use crate::{exit_reason::ExitReason, input_event::InputEvent, loop_event::LoopEvent};
+#[cfg(feature = "watchdog")]
+#[cfg_attr(feature = "docs", doc(cfg(watchdog)))]
+pub use crate::{
+    handler_timeout::HandlerTimeout, watchdog::Watchdog, watchdog_policy::WatchdogPolicy,
+};
+#[cfg(all(feature = "hot_reload", not(target_arch = "wasm32")))]
+#[cfg_attr(feature = "docs", doc(cfg(hot_reload)))]
+pub use crate::{
+    hot_reload::{HotReload, HotReloadFn},
+    hot_reload_error::HotReloadError,
+};
+#[cfg(feature = "input_replay")]
+#[cfg_attr(feature = "docs", doc(cfg(input_replay)))]
+pub use crate::{
+    input_recording::InputRecording, input_replay::InputReplay,
+    input_replay_error::InputReplayError, recorded_tick::RecordedTick,
 };
@@ -0,0 +1,11 @@
+/// Custom event sent to the `winit` event loop.
+///
+/// User events are not sent through `winit`, but through the event loop's
+/// `EventLoopProxy`, which wakes the event loop with this event. `StopHandle`
+/// also wakes the event loop with this event when a stop is requested.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LoopEvent {
+    /// Wakes the event loop, as an event driven event handler has an event, a
+    /// user event has been sent, or a stop has been requested.
+    Wake,
+}
@@ -0,0 +1,163 @@
+use std::{num::NonZeroU32, time::Duration};
+
+use crate::Error;
+
+/// Number of invocations permitted over time.
+///
+/// One invocation is replenished every replenish interval, and up to the burst
+/// size may run back to back after an event handler has fallen behind.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Quota {
+    /// Duration to replenish one invocation.
+    replenish_interval: Duration,
+    /// Maximum number of invocations that may run back to back.
+    burst_size: NonZeroU32,
+    /// Replenish interval for each invocation after the first in a burst,
+    /// computed when the burst size is set so that it cannot overflow.
+    burst_allowance: Duration,
+}
+
+impl Quota {
+    /// Returns a `Quota` that replenishes `max_burst` invocations per second.
+    ///
+    /// The burst size is also `max_burst`.
+    ///
+    /// Returns `None` if `max_burst` is so large that the replenish interval
+    /// rounds down to zero, as the quota would not limit invocations.
+    ///
+    /// # Parameters
+    ///
+    /// * `max_burst`: Number of invocations per second.
+    pub fn per_second(max_burst: NonZeroU32) -> Option<Quota> {
+        let replenish_interval = Duration::from_secs(1) / max_burst.get();
+        if replenish_interval == Duration::from_nanos(0) {
+            return None;
+        }
+
+        Some(Quota {
+            replenish_interval,
+            burst_size: max_burst,
+            burst_allowance: replenish_interval.checked_mul(max_burst.get() - 1)?,
+        })
+    }
+
+    /// Returns a `Quota` that replenishes one invocation per period.
+    ///
+    /// Returns `None` if the period is zero.
+    ///
+    /// # Parameters
+    ///
+    /// * `replenish_1_per`: Duration to replenish one invocation.
+    pub fn with_period(replenish_1_per: Duration) -> Option<Quota> {
+        if replenish_1_per == Duration::from_nanos(0) {
+            None
+        } else {
+            Some(Quota {
+                replenish_interval: replenish_1_per,
+                burst_size: NonZeroU32::new(1).expect("1 is non-zero."),
+                burst_allowance: Duration::from_nanos(0),
+            })
+        }
+    }
+
+    /// Returns this `Quota` with the given burst size.
+    ///
+    /// Returns an error if the burst size is too large to be represented as a
+    /// `Duration` of replenish intervals.
+    ///
+    /// # Parameters
+    ///
+    /// * `max_burst`: Maximum number of invocations that may run back to back.
+    pub fn allow_burst(self, max_burst: NonZeroU32) -> Result<Quota, Error> {
+        let burst_allowance = self
+            .replenish_interval
+            .checked_mul(max_burst.get() - 1)
+            .ok_or(Error::RateLimitBurstOverflow {
+                burst: max_burst.get(),
+                period: self.replenish_interval,
+            })?;
+
+        Ok(Quota {
+            burst_size: max_burst,
+            burst_allowance,
+            ..self
+        })
+    }
+
+    /// Returns the duration to replenish one invocation.
+    pub fn replenish_interval(&self) -> Duration {
+        self.replenish_interval
+    }
+
+    /// Returns the maximum number of invocations that may run back to back.
+    pub fn burst_size(&self) -> NonZeroU32 {
+        self.burst_size
+    }
+
+    /// Returns how far ahead of the current time invocations may be used up,
+    /// which is the replenish interval for each invocation after the first in
+    /// a burst.
+    pub fn burst_allowance(&self) -> Duration {
+        self.burst_allowance
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{num::NonZeroU32, time::Duration};
+
+    use crate::{Error, Quota};
+
+    #[test]
+    fn per_second_replenishes_over_one_second() {
+        let quota = Quota::per_second(NonZeroU32::new(50).unwrap()).expect("Interval is non-zero.");
+
+        assert_eq!(Duration::from_millis(20), quota.replenish_interval());
+        assert_eq!(NonZeroU32::new(50).unwrap(), quota.burst_size());
+        assert_eq!(Duration::from_millis(980), quota.burst_allowance());
+    }
+
+    #[test]
+    fn per_second_returns_none_when_interval_rounds_to_zero() {
+        assert!(Quota::per_second(NonZeroU32::new(1_000_000_000).unwrap()).is_some());
+        assert_eq!(
+            None,
+            Quota::per_second(NonZeroU32::new(1_000_000_001).unwrap())
+        );
+    }
+
+    #[test]
+    fn with_period_zero_returns_none() {
+        assert_eq!(None, Quota::with_period(Duration::from_millis(0)));
+    }
+
+    #[test]
+    fn with_period_has_burst_size_of_one() {
+        assert_eq!(
+            Some(NonZeroU32::new(1).unwrap()),
+            Quota::with_period(Duration::from_millis(11)).map(|quota| quota.burst_size())
+        );
+    }
+
+    #[test]
+    fn allow_burst_returns_error_when_burst_allowance_overflows() {
+        let period = Duration::from_secs(u64::MAX / 2);
+        let quota = Quota::with_period(period).expect("Period is non-zero.");
+
+        assert_eq!(
+            Err(Error::RateLimitBurstOverflow { burst: 4, period }),
+            quota.allow_burst(NonZeroU32::new(4).unwrap())
+        );
+    }
+
+    #[test]
+    fn burst_allowance_is_replenish_interval_per_extra_invocation() -> Result<(), Error> {
+        let quota = Quota::with_period(Duration::from_millis(10))
+            .expect("Period is non-zero.")
+            .allow_burst(NonZeroU32::new(3).unwrap())?;
+
+        assert_eq!(Duration::from_millis(20), quota.burst_allowance());
+
+        Ok(())
+    }
+}
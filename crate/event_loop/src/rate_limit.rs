@@ -1,38 +1,49 @@
 use std::{num::NonZeroU32, time::Duration};
 
-use governor::Quota;
-
-use crate::Error;
+use crate::{Error, Quota};
 
 /// Rate limit event handler execution.
+///
+/// The [`RateLimit::fps`] and [`RateLimit::interval`] functions construct the
+/// base rate, which may be refined with:
+///
+/// * [`RateLimit::with_burst`]: Number of invocations that may run back to
+///   back to catch up after the event handler falls behind.
+/// * [`RateLimit::with_min_spacing`]: Minimum duration between two
+///   invocations, even when catching up.
+/// * [`RateLimit::with_jitter`]: Maximum random delay added before each
+///   invocation, so that event handlers with the same rate do not align.
 #[derive(Clone, Copy, Debug, PartialEq)]
-pub enum RateLimit {
-    /// Maximum number of ticks per second the event handler may run.
-    ///
-    /// The [`RateLimit::fps`] function is provided to construct this variant
-    /// with error checking.
-    Fps(Quota),
+pub struct RateLimit {
+    /// Number of invocations permitted per period, `None` if unlimited.
+    quota: Option<Quota>,
     /// Minimum duration between two invocations of the event handler.
-    ///
-    /// The [`RateLimit::interval`] function is provided to construct this
-    /// variant.
-    Interval(Option<Quota>),
+    min_spacing: Option<Duration>,
+    /// Maximum random delay added before each invocation.
+    jitter: Option<Duration>,
 }
 
 impl RateLimit {
-    /// Returns a `RateLimit::Fps` after validating the fps provided is
-    /// non-zero.
+    /// Returns a `RateLimit` after validating the fps provided is non-zero,
+    /// and at most one tick per nanosecond.
+    ///
+    /// The burst size defaults to `fps`, which may be overridden using
+    /// [`RateLimit::with_burst`].
     ///
     /// # Parameters
     ///
     /// * `fps`: Maximum number of ticks per second the event handler may run.
     pub fn fps(fps: u32) -> Result<Self, Error> {
-        NonZeroU32::new(fps)
-            .ok_or(Error::RateLimitFpsZero)
-            .map(|fps| RateLimit::Fps(Quota::per_second(fps)))
+        let fps_non_zero = NonZeroU32::new(fps).ok_or(Error::RateLimitFpsZero)?;
+
+        Quota::per_second(fps_non_zero)
+            .map(RateLimit::from)
+            .ok_or(Error::RateLimitFpsTooHigh { fps })
     }
 
-    /// Returns a `RateLimit::Interval`.
+    /// Returns a `RateLimit` with a minimum duration between invocations.
+    ///
+    /// A zero `interval` means the event handler is not rate limited.
     ///
     /// # Parameters
     ///
@@ -40,14 +51,94 @@ impl RateLimit {
     pub fn interval(interval: Duration) -> Self {
         let quota = Quota::with_period(interval);
 
-        RateLimit::Interval(quota)
+        RateLimit {
+            quota,
+            min_spacing: None,
+            jitter: None,
+        }
+    }
+
+    /// Sets the number of invocations that may run back to back when the
+    /// event handler has fallen behind its rate.
+    ///
+    /// # Parameters
+    ///
+    /// * `burst`: Maximum number of invocations to catch up on.
+    pub fn with_burst(mut self, burst: u32) -> Result<Self, Error> {
+        let burst = NonZeroU32::new(burst).ok_or(Error::RateLimitBurstZero)?;
+        let quota = self.quota.ok_or(Error::RateLimitBurstUnlimited)?;
+
+        self.quota = Some(quota.allow_burst(burst)?);
+        Ok(self)
+    }
+
+    /// Sets the minimum duration between two invocations of the event handler.
+    ///
+    /// This applies even when catching up on a burst, and must not be longer
+    /// than the rate limit period. An unlimited rate limit has no period, so
+    /// this returns an error for it.
+    ///
+    /// # Parameters
+    ///
+    /// * `min_spacing`: Minimum duration between two invocations.
+    pub fn with_min_spacing(mut self, min_spacing: Duration) -> Result<Self, Error> {
+        let period = self.period().ok_or(Error::RateLimitMinSpacingUnlimited)?;
+        if min_spacing > period {
+            return Err(Error::RateLimitMinSpacingExceedsPeriod {
+                min_spacing,
+                period,
+            });
+        }
+
+        self.min_spacing = Some(min_spacing);
+        Ok(self)
+    }
+
+    /// Sets the maximum random delay added before each invocation.
+    ///
+    /// This must not be longer than the rate limit period. An unlimited rate
+    /// limit has no period, so this returns an error for it.
+    ///
+    /// # Parameters
+    ///
+    /// * `jitter`: Maximum random delay before each invocation.
+    pub fn with_jitter(mut self, jitter: Duration) -> Result<Self, Error> {
+        let period = self.period().ok_or(Error::RateLimitJitterUnlimited)?;
+        if jitter > period {
+            return Err(Error::RateLimitJitterExceedsPeriod { jitter, period });
+        }
+
+        self.jitter = Some(jitter);
+        Ok(self)
     }
 
     /// Returns the quota, if any.
     pub fn quota(self) -> Option<Quota> {
-        match self {
-            RateLimit::Fps(quota) => Some(quota),
-            RateLimit::Interval(quota) => quota,
+        self.quota
+    }
+
+    /// Returns the minimum duration between two invocations, if any.
+    pub fn min_spacing(self) -> Option<Duration> {
+        self.min_spacing
+    }
+
+    /// Returns the maximum random delay added before each invocation, if any.
+    pub fn jitter(self) -> Option<Duration> {
+        self.jitter
+    }
+
+    /// Returns the duration to replenish one invocation, if rate limited.
+    fn period(self) -> Option<Duration> {
+        self.quota.map(|quota| quota.replenish_interval())
+    }
+}
+
+impl From<Quota> for RateLimit {
+    fn from(quota: Quota) -> Self {
+        RateLimit {
+            quota: Some(quota),
+            min_spacing: None,
+            jitter: None,
         }
     }
 }
@@ -66,17 +157,23 @@ mod tests {
     #[test]
     fn fps_non_zero_returns_ok() {
         assert_eq!(
-            Ok(RateLimit::Fps(Quota::per_second(
-                NonZeroU32::new(1).unwrap()
-            ))),
-            RateLimit::fps(1)
+            Quota::per_second(NonZeroU32::new(1).unwrap()).map(RateLimit::from),
+            RateLimit::fps(1).ok()
+        );
+    }
+
+    #[test]
+    fn fps_above_one_per_nanosecond_returns_error() {
+        assert_eq!(
+            Err(Error::RateLimitFpsTooHigh { fps: u32::MAX }),
+            RateLimit::fps(u32::MAX)
         );
     }
 
     #[test]
     fn quota_from_60_fps_returns_quota() -> Result<(), Error> {
         assert_eq!(
-            Some(Quota::per_second(NonZeroU32::new(60).unwrap())),
+            Quota::per_second(NonZeroU32::new(60).unwrap()),
             RateLimit::fps(60)?.quota()
         );
         Ok(())
@@ -89,4 +186,78 @@ mod tests {
             RateLimit::interval(Duration::from_millis(11)).quota()
         );
     }
+
+    #[test]
+    fn with_burst_sets_quota_burst_size() -> Result<(), Error> {
+        assert_eq!(
+            Some(NonZeroU32::new(3).unwrap()),
+            RateLimit::fps(60)?
+                .with_burst(3)?
+                .quota()
+                .map(|quota| quota.burst_size())
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn with_burst_zero_returns_error() -> Result<(), Error> {
+        assert_eq!(Err(Error::RateLimitBurstZero), RateLimit::fps(60)?.with_burst(0));
+        Ok(())
+    }
+
+    #[test]
+    fn with_burst_on_unlimited_returns_error() {
+        assert_eq!(
+            Err(Error::RateLimitBurstUnlimited),
+            RateLimit::interval(Duration::from_millis(0)).with_burst(2)
+        );
+    }
+
+    #[test]
+    fn with_min_spacing_and_jitter_on_unlimited_returns_error() {
+        let unlimited = RateLimit::interval(Duration::from_millis(0));
+
+        assert_eq!(
+            Err(Error::RateLimitMinSpacingUnlimited),
+            unlimited.with_min_spacing(Duration::from_millis(1))
+        );
+        assert_eq!(
+            Err(Error::RateLimitJitterUnlimited),
+            unlimited.with_jitter(Duration::from_millis(1))
+        );
+    }
+
+    #[test]
+    fn with_min_spacing_exceeding_period_returns_error() {
+        assert_eq!(
+            Err(Error::RateLimitMinSpacingExceedsPeriod {
+                min_spacing: Duration::from_millis(12),
+                period: Duration::from_millis(11),
+            }),
+            RateLimit::interval(Duration::from_millis(11))
+                .with_min_spacing(Duration::from_millis(12))
+        );
+    }
+
+    #[test]
+    fn with_jitter_exceeding_period_returns_error() {
+        assert_eq!(
+            Err(Error::RateLimitJitterExceedsPeriod {
+                jitter: Duration::from_millis(12),
+                period: Duration::from_millis(11),
+            }),
+            RateLimit::interval(Duration::from_millis(11)).with_jitter(Duration::from_millis(12))
+        );
+    }
+
+    #[test]
+    fn with_min_spacing_and_jitter_within_period_returns_ok() -> Result<(), Error> {
+        let rate_limit = RateLimit::interval(Duration::from_millis(11))
+            .with_min_spacing(Duration::from_millis(5))?
+            .with_jitter(Duration::from_millis(2))?;
+
+        assert_eq!(Some(Duration::from_millis(5)), rate_limit.min_spacing());
+        assert_eq!(Some(Duration::from_millis(2)), rate_limit.jitter());
+        Ok(())
+    }
 }
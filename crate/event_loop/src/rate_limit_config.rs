@@ -0,0 +1,50 @@
+use std::time::Duration;
+
+use serde::Deserialize;
+
+use crate::{Error, RateLimit};
+
+/// Rate limit of an event handler, declared in an `EventLoopConfig`.
+///
+/// The base rate is `fps` if it is set, otherwise `interval`. When neither is
+/// set, the event handler is not rate limited.
+///
+/// ```ron
+/// (fps: Some(60), burst: Some(2))
+/// ```
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct RateLimitConfig {
+    /// Maximum number of ticks per second the event handler may run.
+    pub fps: Option<u32>,
+    /// Duration to wait between invocations of the event handler.
+    pub interval: Option<Duration>,
+    /// Number of invocations that may run back to back to catch up.
+    pub burst: Option<u32>,
+    /// Minimum duration between two invocations, even when catching up.
+    pub min_spacing: Option<Duration>,
+    /// Maximum random delay added before each invocation.
+    pub jitter: Option<Duration>,
+}
+
+impl RateLimitConfig {
+    /// Returns the `RateLimit` declared by this configuration.
+    pub fn rate_limit(&self) -> Result<RateLimit, Error> {
+        let mut rate_limit = match self.fps {
+            Some(fps) => RateLimit::fps(fps)?,
+            None => RateLimit::interval(self.interval.unwrap_or_default()),
+        };
+
+        if let Some(burst) = self.burst {
+            rate_limit = rate_limit.with_burst(burst)?;
+        }
+        if let Some(min_spacing) = self.min_spacing {
+            rate_limit = rate_limit.with_min_spacing(min_spacing)?;
+        }
+        if let Some(jitter) = self.jitter {
+            rate_limit = rate_limit.with_jitter(jitter)?;
+        }
+
+        Ok(rate_limit)
+    }
+}
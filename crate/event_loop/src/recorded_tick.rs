@@ -0,0 +1,20 @@
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::InputEvent;
+
+/// Input received before one tick of a recorded event loop.
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct RecordedTick {
+    /// Time since the event loop started when the tick was run.
+    pub elapsed: Duration,
+    /// Input received since the previous tick.
+    pub events: Vec<InputEvent>,
+    /// Indices of the event handlers that ran in this tick, in the order they
+    /// ran.
+    ///
+    /// Rate limited and event driven event handlers do not run every tick, so
+    /// these are replayed instead of running every event handler.
+    pub event_handlers: Vec<usize>,
+}
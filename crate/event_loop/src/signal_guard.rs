@@ -0,0 +1,168 @@
+use std::{
+    io, process,
+    thread::{self, JoinHandle},
+};
+
+use signal_hook::{cleanup, iterator::Signals, SIGINT, SIGTERM};
+
+use crate::StopHandle;
+
+/// Stops an event loop when the process receives `SIGINT` or `SIGTERM`.
+///
+/// The first signal requests the event loop to exit gracefully, so the exit
+/// handler runs and `run` returns. A second signal terminates the process
+/// immediately, with exit status `128 + signal`.
+///
+/// Signals are handled until this guard is dropped, after which the default
+/// signal behaviour is restored. `signal-hook` does not support handling a
+/// signal again once its default behaviour is restored, so signals should only
+/// be handled once per process.
+///
+/// This is only available on unix platforms.
+#[derive(Debug)]
+pub struct SignalGuard {
+    /// Signals that are handled.
+    signals: Signals,
+    /// Thread that waits for signals.
+    thread: Option<JoinHandle<()>>,
+}
+
+impl SignalGuard {
+    /// Returns a new `SignalGuard`, registering the signal handlers.
+    ///
+    /// # Parameters
+    ///
+    /// * `stop_handle`: Handle to request the event loop to exit.
+    pub(crate) fn new(stop_handle: StopHandle) -> Result<Self, io::Error> {
+        let signals = Signals::new([SIGINT, SIGTERM])?;
+        let signals_thread = signals.clone();
+
+        let thread = thread::Builder::new()
+            .name(String::from("nginee_signal"))
+            .spawn(move || {
+                let mut is_signalled = false;
+                for signal in signals_thread.forever() {
+                    if is_signalled {
+                        process::exit(128 + signal);
+                    }
+
+                    is_signalled = true;
+                    stop_handle.stop_with_reason(signal_name(signal));
+                }
+            })?;
+
+        Ok(Self {
+            signals,
+            thread: Some(thread),
+        })
+    }
+}
+
+impl Drop for SignalGuard {
+    fn drop(&mut self) {
+        self.signals.close();
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+
+        // Closing `signals` unregisters its hooks, but leaves `signal-hook`'s handler
+        // installed, which would ignore the signals.
+        [SIGINT, SIGTERM].iter().for_each(|signal| {
+            let _ = cleanup::cleanup_signal(*signal);
+        });
+    }
+}
+
+/// Returns the name of a handled signal.
+fn signal_name(signal: i32) -> &'static str {
+    match signal {
+        SIGINT => "SIGINT",
+        SIGTERM => "SIGTERM",
+        _ => "signal",
+    }
+}
+
+#[cfg(all(test, not(feature = "window")))]
+mod tests {
+    use std::{
+        env,
+        os::unix::process::ExitStatusExt,
+        process::{Command, ExitStatus},
+    };
+
+    use crossbeam_channel::SendError;
+
+    use crate::{EventHandler, EventHandlingOutcome, EventLoop};
+
+    /// Environment variable that is set when a test runs in a child process.
+    const CHILD_ENV: &str = "NGINEE_SIGNAL_GUARD_TEST_CHILD";
+
+    #[test]
+    fn signal_stops_event_loop_with_signal_name() -> Result<(), SendError<()>> {
+        if !is_child() {
+            let status =
+                run_in_child("signal_guard::tests::signal_stops_event_loop_with_signal_name");
+            assert!(status.success(), "status: {:?}", status);
+            return Ok(());
+        }
+
+        let event_handler_continue =
+            EventHandler::<SendError<()>>::new(
+                || async move { Ok(EventHandlingOutcome::Continue) },
+            );
+        let event_loop = EventLoop::new(vec![event_handler_continue]);
+        let stop_handle = event_loop.stop_handle();
+        let signal_guard = stop_handle
+            .stop_on_signals()
+            .expect("Failed to register signal handlers.");
+
+        unsafe {
+            libc::raise(libc::SIGTERM);
+        }
+        smol::run(event_loop.run())?;
+        drop(signal_guard);
+
+        assert_eq!(Some(String::from("SIGTERM")), stop_handle.reason());
+
+        Ok(())
+    }
+
+    #[test]
+    fn drop_restores_default_signal_behaviour() {
+        if !is_child() {
+            let status =
+                run_in_child("signal_guard::tests::drop_restores_default_signal_behaviour");
+            assert_eq!(Some(libc::SIGTERM), status.signal(), "status: {:?}", status);
+            return;
+        }
+
+        let event_loop = EventLoop::<SendError<()>>::new(vec![]);
+        let signal_guard = event_loop
+            .stop_handle()
+            .stop_on_signals()
+            .expect("Failed to register signal handlers.");
+        drop(signal_guard);
+
+        // The default behaviour terminates the process.
+        unsafe {
+            libc::raise(libc::SIGTERM);
+        }
+    }
+
+    /// Returns whether this test is running in a child process.
+    fn is_child() -> bool {
+        env::var_os(CHILD_ENV).is_some()
+    }
+
+    /// Runs the named test in a child process, as signals affect the whole
+    /// process.
+    fn run_in_child(test_name: &str) -> ExitStatus {
+        let test_exe = env::current_exe().expect("Failed to get test executable path.");
+
+        Command::new(test_exe)
+            .args([test_name, "--exact", "--test-threads=1"])
+            .env(CHILD_ENV, "1")
+            .status()
+            .expect("Failed to run test in child process.")
+    }
+}
@@ -0,0 +1,136 @@
+use std::future::Future;
+
+/// Spawns background tasks on the executor selected by cargo features.
+///
+/// This lets event handlers spawn tasks the same way regardless of the
+/// executor that runs the event loop. The backend is chosen by enabling one of
+/// the following features:
+///
+/// * `"tokio"`: Spawns onto the current `tokio` runtime. Outside a `tokio`
+///   runtime, this falls back to another enabled backend, or runs the task on
+///   its own thread.
+/// * `"async-std"`: Spawns onto the `async-std` global executor.
+/// * `"smol"`: Spawns onto the `smol` global executor.
+/// * `"wasm-bindgen-futures"`: Spawns onto the browser's microtask queue on
+///   WASM.
+///
+/// When more than one native backend is enabled, the current `tokio` runtime
+/// is preferred, then `async-std`, then `smol`.
+///
+/// Spawned tasks are detached. To receive a task's output, send it through a
+/// channel, or as a user event through an `EventLoopProxy`, which wakes the
+/// event loop.
+///
+/// This is obtained through `EventLoop::spawner`, and may be copied into
+/// event handlers.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Spawner {
+    /// Prevents construction outside this crate.
+    _private: (),
+}
+
+impl Spawner {
+    /// Returns a new `Spawner`.
+    pub(crate) fn new() -> Self {
+        Self::default()
+    }
+
+    /// Spawns a task to run in the background.
+    ///
+    /// # Parameters
+    ///
+    /// * `future`: The task to run.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn spawn<Fut>(&self, future: Fut)
+    where
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        #[cfg(feature = "tokio")]
+        let future = match tokio::runtime::Handle::try_current() {
+            Ok(handle) => {
+                handle.spawn(future);
+                return;
+            }
+            Err(_) => future,
+        };
+
+        #[cfg(feature = "async-std")]
+        {
+            async_std::task::spawn(future);
+        }
+
+        #[cfg(all(feature = "smol", not(feature = "async-std")))]
+        {
+            smol::Task::spawn(future).detach();
+        }
+
+        // `tokio::spawn` panics outside a runtime, so the task runs on its own thread and runtime.
+        #[cfg(all(feature = "tokio", not(any(feature = "async-std", feature = "smol"))))]
+        {
+            std::thread::spawn(move || {
+                let mut runtime = tokio::runtime::Builder::new()
+                    .basic_scheduler()
+                    .build()
+                    .expect("Failed to build `tokio` runtime for spawned task.");
+                runtime.block_on(future);
+            });
+        }
+    }
+
+    /// Spawns a task to run in the background.
+    ///
+    /// On WASM, the task does not need to be `Send`, so it may use web APIs.
+    ///
+    /// # Parameters
+    ///
+    /// * `future`: The task to run.
+    #[cfg(target_arch = "wasm32")]
+    pub fn spawn<Fut>(&self, future: Fut)
+    where
+        Fut: Future<Output = ()> + 'static,
+    {
+        wasm_bindgen_futures::spawn_local(future);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::channel::oneshot;
+
+    use super::Spawner;
+
+    #[test]
+    fn spawn_runs_task_in_background() {
+        let (tx, rx) = oneshot::channel();
+
+        let received = smol::run(async move {
+            Spawner::new().spawn(async move {
+                let _ = tx.send(1);
+            });
+
+            rx.await
+        });
+
+        assert_eq!(Ok(1), received);
+    }
+
+    #[cfg(feature = "tokio")]
+    #[test]
+    fn spawn_runs_task_on_current_tokio_runtime() {
+        let (tx, rx) = oneshot::channel();
+        let mut runtime = tokio::runtime::Builder::new()
+            .basic_scheduler()
+            .build()
+            .expect("Failed to build tokio runtime.");
+
+        let received = runtime.block_on(async move {
+            Spawner::new().spawn(async move {
+                let _ = tx.send(tokio::runtime::Handle::try_current().is_ok());
+            });
+
+            rx.await
+        });
+
+        assert_eq!(Ok(true), received);
+    }
+}
@@ -0,0 +1,119 @@
+use std::borrow::Cow;
+
+use crate::{state_hooks::StateHooks, EventHandler};
+
+/// A game state, such as a main menu or gameplay, with its own event handlers.
+///
+/// States are added to the event loop with `EventLoop::with_state`, and
+/// transitioned between through its `StateStack`. A state's event handlers
+/// only run while it is on top of the stack.
+///
+/// # Type Parameters
+///
+/// * `E`: Error type.
+#[derive(Debug)]
+pub struct State<E> {
+    /// Name of the state, which is also the group of its event handlers.
+    name: Cow<'static, str>,
+    /// `EventHandler`s to run while this state is on top of the stack.
+    event_handlers: Vec<EventHandler<E>>,
+    /// Functions to run when the state transitions.
+    hooks: StateHooks,
+}
+
+impl<E> State<E> {
+    /// Returns a new `State` without event handlers.
+    ///
+    /// # Parameters
+    ///
+    /// * `name`: Name of the state, used to transition to it.
+    pub fn new<N>(name: N) -> Self
+    where
+        N: Into<Cow<'static, str>>,
+    {
+        Self {
+            name: name.into(),
+            event_handlers: Vec::new(),
+            hooks: StateHooks::default(),
+        }
+    }
+
+    /// Adds an event handler to run while this state is on top of the stack.
+    ///
+    /// # Parameters
+    ///
+    /// * `event_handler`: The event handler to add.
+    pub fn with_event_handler(mut self, event_handler: EventHandler<E>) -> Self {
+        self.event_handlers.push(event_handler);
+        self
+    }
+
+    /// Sets a function to run when this state is pushed onto the stack.
+    ///
+    /// # Parameters
+    ///
+    /// * `on_enter`: Function to run.
+    pub fn with_on_enter<F>(mut self, on_enter: F) -> Self
+    where
+        F: FnMut() + 'static,
+    {
+        self.hooks.on_enter = Some(Box::new(on_enter));
+        self
+    }
+
+    /// Sets a function to run when this state is popped off the stack.
+    ///
+    /// # Parameters
+    ///
+    /// * `on_exit`: Function to run.
+    pub fn with_on_exit<F>(mut self, on_exit: F) -> Self
+    where
+        F: FnMut() + 'static,
+    {
+        self.hooks.on_exit = Some(Box::new(on_exit));
+        self
+    }
+
+    /// Sets a function to run when another state is pushed on top of this
+    /// state.
+    ///
+    /// # Parameters
+    ///
+    /// * `on_pause`: Function to run.
+    pub fn with_on_pause<F>(mut self, on_pause: F) -> Self
+    where
+        F: FnMut() + 'static,
+    {
+        self.hooks.on_pause = Some(Box::new(on_pause));
+        self
+    }
+
+    /// Sets a function to run when the state above this state is popped.
+    ///
+    /// # Parameters
+    ///
+    /// * `on_resume`: Function to run.
+    pub fn with_on_resume<F>(mut self, on_resume: F) -> Self
+    where
+        F: FnMut() + 'static,
+    {
+        self.hooks.on_resume = Some(Box::new(on_resume));
+        self
+    }
+
+    /// Returns the name of this state.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the name, event handlers, and hooks of this state.
+    pub(crate) fn into_parts(self) -> (Cow<'static, str>, Vec<EventHandler<E>>, StateHooks) {
+        let State {
+            name,
+            event_handlers,
+            hooks,
+        } = self;
+
+        (name, event_handlers, hooks)
+    }
+}
@@ -0,0 +1,31 @@
+use core::fmt::{self, Debug};
+
+/// Function run when a state transitions.
+pub(crate) type StateHook = Box<dyn FnMut()>;
+
+/// Functions run when a state is entered, exited, paused, or resumed.
+#[derive(Default)]
+pub(crate) struct StateHooks {
+    /// Runs when the state is pushed onto the stack.
+    pub on_enter: Option<StateHook>,
+    /// Runs when the state is popped off the stack.
+    pub on_exit: Option<StateHook>,
+    /// Runs when another state is pushed on top of this state.
+    pub on_pause: Option<StateHook>,
+    /// Runs when this state is on top of the stack again.
+    pub on_resume: Option<StateHook>,
+}
+
+#[cfg_attr(tarpaulin, skip)]
+impl Debug for StateHooks {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let hook = |hook: &Option<StateHook>| if hook.is_some() { "Some(..)" } else { "None" };
+
+        f.debug_struct("StateHooks")
+            .field("on_enter", &hook(&self.on_enter))
+            .field("on_exit", &hook(&self.on_exit))
+            .field("on_pause", &hook(&self.on_pause))
+            .field("on_resume", &hook(&self.on_resume))
+            .finish()
+    }
+}
@@ -0,0 +1,278 @@
+use std::{
+    borrow::Cow,
+    cell::RefCell,
+    collections::{HashMap, VecDeque},
+    error::Error,
+    rc::Rc,
+};
+
+use crate::{
+    state_hooks::{StateHook, StateHooks},
+    EventHandler, HandlerGroups, State,
+};
+
+/// Stack of game states, such as `MainMenu`, `Playing`, and `Paused`.
+///
+/// States are added with `EventLoop::with_state`, and the state on top of the
+/// stack is the current state. Only the current state's event handlers run;
+/// states beneath it are paused.
+///
+/// Transitions are requested with [`StateStack::push`], [`StateStack::pop`],
+/// and [`StateStack::switch`], and are applied by the event loop after the
+/// event handlers of the current tick have run, so that every event handler
+/// in a tick sees the same state. Transitions requested before the event loop
+/// runs are applied before its first tick.
+///
+/// This is obtained through `EventLoop::state_stack`, and may be cloned into
+/// event handlers and state hooks.
+#[derive(Clone, Debug)]
+pub struct StateStack {
+    /// Names of the states on the stack, with the current state last.
+    stack: Rc<RefCell<Vec<Cow<'static, str>>>>,
+    /// Transitions that have been requested but not applied.
+    transitions: Rc<RefCell<VecDeque<StateTransition>>>,
+    /// Hooks of each state added to the event loop.
+    hooks: Rc<RefCell<HashMap<Cow<'static, str>, StateHooks>>>,
+    /// Enables the current state's event handlers, and disables the others.
+    handler_groups: HandlerGroups,
+}
+
+/// Change to the state stack.
+#[derive(Clone, Debug)]
+enum StateTransition {
+    /// Pauses the current state, and enters the given state.
+    Push(Cow<'static, str>),
+    /// Exits the current state, and resumes the state beneath it.
+    Pop,
+    /// Exits the current state, and enters the given state.
+    Switch(Cow<'static, str>),
+}
+
+impl StateStack {
+    /// Returns a new, empty `StateStack`.
+    ///
+    /// # Parameters
+    ///
+    /// * `handler_groups`: Handler groups of the event loop.
+    pub(crate) fn new(handler_groups: HandlerGroups) -> Self {
+        Self {
+            stack: Rc::new(RefCell::new(Vec::new())),
+            transitions: Rc::new(RefCell::new(VecDeque::new())),
+            hooks: Rc::new(RefCell::new(HashMap::new())),
+            handler_groups,
+        }
+    }
+
+    /// Requests the given state to be entered, pausing the current state.
+    ///
+    /// # Parameters
+    ///
+    /// * `state`: Name of the state to enter.
+    pub fn push<N>(&self, state: N)
+    where
+        N: Into<Cow<'static, str>>,
+    {
+        self.request(StateTransition::Push(state.into()));
+    }
+
+    /// Requests the current state to be exited, resuming the state beneath
+    /// it.
+    pub fn pop(&self) {
+        self.request(StateTransition::Pop);
+    }
+
+    /// Requests the current state to be exited and replaced with the given
+    /// state.
+    ///
+    /// # Parameters
+    ///
+    /// * `state`: Name of the state to enter.
+    pub fn switch<N>(&self, state: N)
+    where
+        N: Into<Cow<'static, str>>,
+    {
+        self.request(StateTransition::Switch(state.into()));
+    }
+
+    /// Returns the name of the current state, if any.
+    pub fn current(&self) -> Option<Cow<'static, str>> {
+        self.stack.borrow().last().cloned()
+    }
+
+    /// Returns the names of the states on the stack, with the current state
+    /// last.
+    pub fn states(&self) -> Vec<Cow<'static, str>> {
+        self.stack.borrow().clone()
+    }
+
+    /// Adds a state's event handlers to the given event handlers, and registers
+    /// its hooks.
+    ///
+    /// The state's event handlers are added to the group named after the state.
+    ///
+    /// # Parameters
+    ///
+    /// * `state`: The state to add.
+    /// * `event_handlers`: Event handlers of the event loop.
+    pub(crate) fn add_state<E>(&self, state: State<E>, event_handlers: &mut Vec<EventHandler<E>>)
+    where
+        E: Error + 'static,
+    {
+        let (name, state_event_handlers, hooks) = state.into_parts();
+        self.register(name.clone(), hooks);
+        event_handlers.extend(
+            state_event_handlers
+                .into_iter()
+                .map(|event_handler| event_handler.with_group(name.clone())),
+        );
+    }
+
+    /// Adds a state that may be transitioned to.
+    ///
+    /// The state's event handler group is disabled until the state is entered.
+    ///
+    /// # Parameters
+    ///
+    /// * `state`: Name of the state.
+    /// * `hooks`: Functions to run when the state transitions.
+    pub(crate) fn register(&self, state: Cow<'static, str>, hooks: StateHooks) {
+        self.handler_groups.disable(state.clone());
+        self.hooks.borrow_mut().insert(state, hooks);
+    }
+
+    /// Applies the requested transitions in order, running the state hooks.
+    ///
+    /// Transitions requested by the hooks are also applied. Returns whether
+    /// any transition was applied.
+    pub(crate) fn apply_transitions(&self) -> bool {
+        let mut is_applied = false;
+
+        loop {
+            // Bound separately so the hooks may request further transitions.
+            let transition = self.transitions.borrow_mut().pop_front();
+            match transition {
+                Some(StateTransition::Push(state)) => {
+                    if let Some(current) = self.current() {
+                        self.handler_groups.disable(current.clone());
+                        self.run_hook(&current, |hooks| &mut hooks.on_pause);
+                    }
+                    self.enter(state);
+                }
+                Some(StateTransition::Pop) => {
+                    self.exit();
+                    if let Some(current) = self.current() {
+                        self.handler_groups.enable(&current);
+                        self.run_hook(&current, |hooks| &mut hooks.on_resume);
+                    }
+                }
+                Some(StateTransition::Switch(state)) => {
+                    self.exit();
+                    self.enter(state);
+                }
+                None => break,
+            }
+
+            is_applied = true;
+        }
+
+        is_applied
+    }
+
+    /// Queues a transition to be applied by the event loop.
+    fn request(&self, transition: StateTransition) {
+        self.transitions.borrow_mut().push_back(transition);
+    }
+
+    /// Pushes the given state onto the stack, and enables its event handlers.
+    fn enter(&self, state: Cow<'static, str>) {
+        self.stack.borrow_mut().push(state.clone());
+        self.handler_groups.enable(&state);
+        self.run_hook(&state, |hooks| &mut hooks.on_enter);
+    }
+
+    /// Pops the current state off the stack, and disables its event handlers.
+    fn exit(&self) {
+        let state = self.stack.borrow_mut().pop();
+        if let Some(state) = state {
+            self.handler_groups.disable(state.clone());
+            self.run_hook(&state, |hooks| &mut hooks.on_exit);
+        }
+    }
+
+    /// Runs a hook of the given state, if the state has that hook.
+    fn run_hook<F>(&self, state: &str, hook: F)
+    where
+        F: FnOnce(&mut StateHooks) -> &mut Option<StateHook>,
+    {
+        if let Some(hook) = self
+            .hooks
+            .borrow_mut()
+            .get_mut(state)
+            .and_then(|hooks| hook(hooks).as_mut())
+        {
+            hook();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::RefCell, rc::Rc};
+
+    use super::StateStack;
+    use crate::{state_hooks::StateHooks, HandlerGroups};
+
+    #[test]
+    fn apply_transitions_runs_hooks_and_enables_current_state() {
+        let handler_groups = HandlerGroups::new();
+        let state_stack = StateStack::new(handler_groups.clone());
+        let log = Rc::new(RefCell::new(Vec::new()));
+
+        ["menu", "playing", "paused"].iter().for_each(|state| {
+            let hook = |event: &'static str| {
+                let log = log.clone();
+                Some(
+                    Box::new(move || log.borrow_mut().push(format!("{} {}", event, state)))
+                        as Box<dyn FnMut()>,
+                )
+            };
+            let hooks = StateHooks {
+                on_enter: hook("enter"),
+                on_exit: hook("exit"),
+                on_pause: hook("pause"),
+                on_resume: hook("resume"),
+            };
+            state_stack.register((*state).into(), hooks);
+        });
+
+        state_stack.push("menu");
+        state_stack.switch("playing");
+        state_stack.push("paused");
+        assert!(state_stack.apply_transitions());
+
+        assert_eq!(vec!["playing", "paused"], state_stack.states());
+        assert!(!handler_groups.is_enabled("menu"));
+        assert!(!handler_groups.is_enabled("playing"));
+        assert!(handler_groups.is_enabled("paused"));
+
+        state_stack.pop();
+        assert!(state_stack.apply_transitions());
+        assert!(!state_stack.apply_transitions());
+
+        assert_eq!(Some("playing".into()), state_stack.current());
+        assert!(handler_groups.is_enabled("playing"));
+        assert!(!handler_groups.is_enabled("paused"));
+        assert_eq!(
+            vec![
+                "enter menu",
+                "exit menu",
+                "enter playing",
+                "pause playing",
+                "enter paused",
+                "exit paused",
+                "resume playing",
+            ],
+            *log.borrow()
+        );
+    }
+}
@@ -0,0 +1,226 @@
+#[cfg(all(feature = "signal", unix))]
+use std::io;
+#[cfg(not(feature = "window"))]
+use std::task::{Context, Poll};
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex, MutexGuard, PoisonError,
+    },
+    task::Waker,
+};
+
+#[cfg(all(feature = "signal", unix))]
+use crate::SignalGuard;
+
+/// Requests an `EventLoop` to exit gracefully.
+///
+/// This is obtained through `EventLoop::stop_handle`, and may be cloned and
+/// sent to other threads or tasks. The stop request is honoured at the start
+/// of the event loop's next tick, so event handlers that are running are not
+/// interrupted.
+#[derive(Clone, Debug, Default)]
+pub struct StopHandle {
+    /// State shared with the event loop.
+    state: Arc<StopState>,
+}
+
+/// State shared between `StopHandle`s and the event loop.
+#[derive(Debug, Default)]
+struct StopState {
+    /// Whether a stop has been requested.
+    stop_requested: AtomicBool,
+    /// Reason for stopping the event loop.
+    reason: Mutex<Option<String>>,
+    /// Wakes the event loop when it is idle.
+    waker: Mutex<Option<Waker>>,
+}
+
+impl StopHandle {
+    /// Returns a new `StopHandle`.
+    pub(crate) fn new() -> Self {
+        Self::default()
+    }
+
+    /// Requests the event loop to exit.
+    pub fn stop(&self) {
+        self.request_stop(None);
+    }
+
+    /// Requests the event loop to exit, with the reason passed to the exit
+    /// handler.
+    ///
+    /// If a stop has already been requested, the reason is only recorded if
+    /// the earlier request did not have one.
+    ///
+    /// # Parameters
+    ///
+    /// * `reason`: Why the event loop is being stopped.
+    pub fn stop_with_reason<R>(&self, reason: R)
+    where
+        R: Into<String>,
+    {
+        self.request_stop(Some(reason.into()));
+    }
+
+    /// Requests the event loop to exit when the process receives `SIGINT` or
+    /// `SIGTERM`.
+    ///
+    /// The reason passed to the exit handler is the signal name. A second
+    /// signal terminates the process immediately.
+    ///
+    /// Signals are handled until the returned `SignalGuard` is dropped. This is
+    /// only available on unix platforms.
+    #[cfg(all(feature = "signal", unix))]
+    #[cfg_attr(feature = "docs", doc(cfg(all(signal, unix))))]
+    pub fn stop_on_signals(&self) -> Result<SignalGuard, io::Error> {
+        SignalGuard::new(self.clone())
+    }
+
+    /// Returns whether a stop has been requested.
+    pub fn is_stop_requested(&self) -> bool {
+        self.state.stop_requested.load(Ordering::Acquire)
+    }
+
+    /// Returns the reason the stop was requested, if any.
+    pub fn reason(&self) -> Option<String> {
+        lock(&self.state.reason).clone()
+    }
+
+    /// Returns `Ready` once a stop has been requested, otherwise registers the
+    /// waker to be woken when it is.
+    #[cfg(not(feature = "window"))]
+    pub(crate) fn poll_stop_requested(&self, cx: &mut Context<'_>) -> Poll<()> {
+        if self.is_stop_requested() {
+            return Poll::Ready(());
+        }
+
+        *lock(&self.state.waker) = Some(cx.waker().clone());
+
+        // Check again, in case the stop was requested before the waker was stored.
+        if self.is_stop_requested() {
+            Poll::Ready(())
+        } else {
+            Poll::Pending
+        }
+    }
+
+    /// Registers the waker to be woken when a stop is requested.
+    ///
+    /// This wakes the `winit` event loop when it is waiting for events, so that
+    /// a stop requested from another thread is honoured promptly.
+    ///
+    /// # Parameters
+    ///
+    /// * `waker`: Waker that sends a wake event to the `winit` event loop.
+    #[cfg(feature = "window")]
+    pub(crate) fn wake_on_stop(&self, waker: Waker) {
+        *lock(&self.state.waker) = Some(waker);
+
+        // Wake now, in case the stop was requested before the waker was stored.
+        if self.is_stop_requested() {
+            if let Some(waker) = lock(&self.state.waker).take() {
+                waker.wake();
+            }
+        }
+    }
+
+    fn request_stop(&self, reason: Option<String>) {
+        if let Some(reason) = reason {
+            let mut reason_existing = lock(&self.state.reason);
+            if reason_existing.is_none() {
+                *reason_existing = Some(reason);
+            }
+        }
+
+        self.state.stop_requested.store(true, Ordering::Release);
+
+        if let Some(waker) = lock(&self.state.waker).take() {
+            waker.wake();
+        }
+    }
+}
+
+/// Locks the mutex, ignoring poisoning as the guarded values are always valid.
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(feature = "window")]
+    use std::sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    };
+
+    #[cfg(feature = "window")]
+    use futures::task::{self, ArcWake};
+
+    use super::StopHandle;
+
+    #[test]
+    fn stop_is_visible_through_clones() {
+        let stop_handle = StopHandle::new();
+        let stop_handle_clone = stop_handle.clone();
+
+        assert!(!stop_handle.is_stop_requested());
+
+        stop_handle_clone.stop();
+
+        assert!(stop_handle.is_stop_requested());
+        assert_eq!(None, stop_handle.reason());
+    }
+
+    #[test]
+    fn stop_with_reason_keeps_first_reason() {
+        let stop_handle = StopHandle::new();
+
+        stop_handle.stop();
+        stop_handle.stop_with_reason("first");
+        stop_handle.stop_with_reason("second");
+
+        assert!(stop_handle.is_stop_requested());
+        assert_eq!(Some(String::from("first")), stop_handle.reason());
+    }
+
+    #[cfg(feature = "window")]
+    #[test]
+    fn stop_wakes_registered_waker() {
+        let stop_handle = StopHandle::new();
+        let flag_waker = Arc::new(FlagWaker::default());
+        stop_handle.wake_on_stop(task::waker(flag_waker.clone()));
+
+        assert!(!flag_waker.woken.load(Ordering::SeqCst));
+
+        stop_handle.stop();
+
+        assert!(flag_waker.woken.load(Ordering::SeqCst));
+    }
+
+    #[cfg(feature = "window")]
+    #[test]
+    fn wake_on_stop_wakes_immediately_when_stop_already_requested() {
+        let stop_handle = StopHandle::new();
+        let flag_waker = Arc::new(FlagWaker::default());
+
+        stop_handle.stop();
+        stop_handle.wake_on_stop(task::waker(flag_waker.clone()));
+
+        assert!(flag_waker.woken.load(Ordering::SeqCst));
+    }
+
+    /// Records whether it has been woken.
+    #[cfg(feature = "window")]
+    #[derive(Debug, Default)]
+    struct FlagWaker {
+        woken: AtomicBool,
+    }
+
+    #[cfg(feature = "window")]
+    impl ArcWake for FlagWaker {
+        fn wake_by_ref(arc_self: &Arc<Self>) {
+            arc_self.woken.store(true, Ordering::SeqCst);
+        }
+    }
+}
@@ -0,0 +1,228 @@
+use core::{
+    fmt::{self, Debug},
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll},
+};
+use std::time::Duration;
+
+use futures_timer::Delay;
+use instant::Instant;
+
+use crate::{EventHandlerResult, EventHandlingOutcome, HandlerTimeout, WatchdogPolicy};
+
+/// Detects an event handler that runs for longer than a hard deadline.
+///
+/// When the timeout elapses, the timeout is passed to the reporter, and the
+/// policy is applied.
+///
+/// The timeout is only checked when the event handler's future returns
+/// control to the executor, so an event handler that blocks its thread
+/// synchronously, such as in a busy loop or a blocking call, cannot be
+/// detected.
+///
+/// # Type Parameters
+///
+/// * `E`: Error type.
+pub struct Watchdog<E> {
+    /// Maximum duration an event handler may run for.
+    timeout: Duration,
+    /// What to do when the timeout elapses.
+    policy: WatchdogPolicy<E>,
+    /// Reports event handlers that exceed the timeout.
+    reporter: fn(&HandlerTimeout),
+}
+
+impl<E> Watchdog<E> {
+    /// Returns a new `Watchdog`.
+    ///
+    /// # Parameters
+    ///
+    /// * `timeout`: Maximum duration an event handler may run for.
+    /// * `policy`: What to do when the timeout elapses.
+    /// * `reporter`: Function to report the timeout, e.g. to a logger.
+    pub fn new(
+        timeout: Duration,
+        policy: WatchdogPolicy<E>,
+        reporter: fn(&HandlerTimeout),
+    ) -> Self {
+        Self {
+            timeout,
+            policy,
+            reporter,
+        }
+    }
+
+    /// Returns the maximum duration an event handler may run for.
+    pub fn timeout(&self) -> Duration {
+        self.timeout
+    }
+
+    /// Returns what to do when the timeout elapses.
+    pub fn policy(&self) -> &WatchdogPolicy<E> {
+        &self.policy
+    }
+
+    /// Reports an event handler that has exceeded the timeout, and applies the
+    /// policy.
+    ///
+    /// Returns the event handler's result if the policy cancels it, or `None`
+    /// if the event handler continues running.
+    ///
+    /// # Parameters
+    ///
+    /// * `name`: Name of the event handler.
+    /// * `elapsed`: Duration the event handler has run for.
+    pub(crate) fn timed_out(&self, name: &str, elapsed: Duration) -> Option<EventHandlerResult<E>> {
+        let handler_timeout = HandlerTimeout {
+            name: String::from(name),
+            elapsed,
+            timeout: self.timeout,
+        };
+        (self.reporter)(&handler_timeout);
+
+        match self.policy {
+            WatchdogPolicy::CancelAndError(fn_error) => Some(Err(fn_error(handler_timeout))),
+            WatchdogPolicy::CancelAndSkip => Some(Ok(EventHandlingOutcome::Continue)),
+            WatchdogPolicy::Warn => None,
+        }
+    }
+}
+
+/// Timer that a `Watchdog` uses to watch each run of an event handler.
+///
+/// The timer is allocated on the first watched run, and reset for each
+/// following run, so watching a run does not allocate. On `wasm32`,
+/// `futures_timer` schedules a new JS timeout when the timer is reset.
+#[derive(Debug, Default)]
+pub(crate) struct WatchdogTimer {
+    /// Timer that elapses at the timeout, `None` until the first watched run.
+    delay: Option<Delay>,
+    /// When the watched run started, `None` while no run is watched.
+    start: Option<Instant>,
+}
+
+impl WatchdogTimer {
+    /// Starts watching a run.
+    ///
+    /// # Parameters
+    ///
+    /// * `timeout`: Maximum duration the run may take.
+    pub(crate) fn start(&mut self, timeout: Duration) {
+        self.start = Some(Instant::now());
+        match self.delay.as_mut() {
+            Some(delay) => delay.reset(timeout),
+            None => self.delay = Some(Delay::new(timeout)),
+        }
+    }
+
+    /// Stops watching the run.
+    pub(crate) fn stop(&mut self) {
+        self.start = None;
+    }
+
+    /// Returns `Ready` with the duration the run has taken once the timeout
+    /// elapses, and stops watching the run.
+    pub(crate) fn poll_elapsed(&mut self, cx: &mut Context<'_>) -> Poll<Duration> {
+        match (self.start, self.delay.as_mut()) {
+            (Some(start), Some(delay)) => match Pin::new(delay).poll(cx) {
+                Poll::Ready(()) => {
+                    self.start = None;
+                    Poll::Ready(start.elapsed())
+                }
+                Poll::Pending => Poll::Pending,
+            },
+            _ => Poll::Pending,
+        }
+    }
+}
+
+impl<E> Clone for Watchdog<E> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<E> Copy for Watchdog<E> {}
+
+#[cfg_attr(tarpaulin, skip)]
+impl<E> Debug for Watchdog<E> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Watchdog")
+            .field("timeout", &self.timeout)
+            .field("policy", &self.policy)
+            .field("reporter", &"..")
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        sync::atomic::{AtomicUsize, Ordering},
+        time::Duration,
+    };
+
+    use futures_timer::Delay;
+
+    use crate::{EventHandler, EventHandlingOutcome, HandlerTimeout, Watchdog, WatchdogPolicy};
+
+    #[test]
+    fn cancel_and_error_returns_error_with_name_and_elapsed() {
+        let mut event_handler = hung().with_name("hung").with_watchdog(Watchdog::new(
+            Duration::from_millis(5),
+            WatchdogPolicy::CancelAndError(core::convert::identity),
+            |_| {},
+        ));
+
+        let handler_timeout = smol::run(event_handler.run()).unwrap_err();
+
+        assert_eq!("hung", handler_timeout.name);
+        assert_eq!(Duration::from_millis(5), handler_timeout.timeout);
+        assert!(handler_timeout.elapsed >= handler_timeout.timeout);
+    }
+
+    #[test]
+    fn cancel_and_skip_returns_continue() {
+        let mut event_handler = hung().with_watchdog(Watchdog::new(
+            Duration::from_millis(5),
+            WatchdogPolicy::CancelAndSkip,
+            |_| {},
+        ));
+
+        assert_eq!(
+            Ok(EventHandlingOutcome::Continue),
+            smol::run(event_handler.run())
+        );
+    }
+
+    #[test]
+    fn warn_reports_timeout_and_waits_for_event_handler() {
+        static REPORT_COUNT: AtomicUsize = AtomicUsize::new(0);
+
+        let mut event_handler = EventHandler::<HandlerTimeout>::new(|| async move {
+            Delay::new(Duration::from_millis(20)).await;
+            Ok(EventHandlingOutcome::Exit)
+        })
+        .with_watchdog(Watchdog::new(
+            Duration::from_millis(5),
+            WatchdogPolicy::Warn,
+            |_| {
+                REPORT_COUNT.fetch_add(1, Ordering::SeqCst);
+            },
+        ));
+
+        assert_eq!(
+            Ok(EventHandlingOutcome::Exit),
+            smol::run(event_handler.run())
+        );
+        assert_eq!(1, REPORT_COUNT.load(Ordering::SeqCst));
+    }
+
+    fn hung() -> EventHandler<HandlerTimeout> {
+        EventHandler::<HandlerTimeout>::new(|| async move {
+            futures::future::pending::<()>().await;
+            Ok(EventHandlingOutcome::Continue)
+        })
+    }
+}
@@ -0,0 +1,41 @@
+use core::fmt::{self, Debug};
+
+use crate::HandlerTimeout;
+
+/// What to do when an event handler exceeds its watchdog timeout.
+///
+/// # Type Parameters
+///
+/// * `E`: Error type.
+pub enum WatchdogPolicy<E> {
+    /// Cancels the event handler, and stops the event loop with the error
+    /// returned by the given function.
+    CancelAndError(fn(HandlerTimeout) -> E),
+    /// Cancels the event handler, and continues the event loop.
+    ///
+    /// The event handler runs again on its next tick.
+    CancelAndSkip,
+    /// Reports the timeout, and keeps waiting for the event handler.
+    Warn,
+}
+
+// Manual implementations, as deriving these would require `E` to implement
+// them, even though `E` is only used as a function return type.
+impl<E> Clone for WatchdogPolicy<E> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<E> Copy for WatchdogPolicy<E> {}
+
+#[cfg_attr(tarpaulin, skip)]
+impl<E> Debug for WatchdogPolicy<E> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::CancelAndError(_) => f.debug_tuple("CancelAndError").field(&"..").finish(),
+            Self::CancelAndSkip => f.write_str("CancelAndSkip"),
+            Self::Warn => f.write_str("Warn"),
+        }
+    }
+}
@@ -0,0 +1,766 @@
+#![cfg(not(feature = "window"))]
+
+use std::{
+    cell::{Cell, RefCell},
+    fmt, io,
+    rc::Rc,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+use crossbeam_channel::SendError;
+use futures::stream;
+use nginee_event_loop::{EventHandler, EventHandlingOutcome, EventLoop, EventLoopClosed, State};
+#[cfg(feature = "rate_limit")]
+use nginee_event_loop::{IdleStrategy, RateLimit};
+use nginee_test::{mock, Recorder};
+use smol::Timer;
+
+#[test]
+fn run_runs_event_handlers_until_exit_is_signalled() -> Result<(), SendError<()>> {
+    let recorder = Recorder::new();
+    let event_handler_send = mock::spy(recorder.clone(), "sender");
+    let event_handler_countdown = mock::countdown(3);
+
+    let event_loop = EventLoop::new(vec![event_handler_send, event_handler_countdown]);
+
+    smol::run(event_loop.run())?;
+
+    recorder.assert_count("sender", 3);
+
+    Ok(())
+}
+
+#[test]
+fn run_returns_on_first_error() -> Result<(), SendError<()>> {
+    let recorder = Recorder::new();
+    let event_handler_send = mock::spy(recorder.clone(), "sender");
+    let event_handler_countdown = mock::countdown(3);
+
+    let event_loop = EventLoop::new(vec![
+        event_handler_send,
+        mock::errorer(SendError(())),
+        event_handler_countdown,
+    ]);
+
+    assert_eq!(SendError(()), smol::run(event_loop.run()).unwrap_err());
+    recorder.assert_count("sender", 1);
+
+    Ok(())
+}
+
+#[cfg(feature = "rate_limit")]
+#[test]
+fn event_handlers_are_rate_limited_independently() -> Result<(), SendError<()>> {
+    let recorder = Recorder::new();
+    let event_handler_send_0 = mock::spy(recorder.clone(), "sender_0");
+
+    let event_handler_send_1 = mock::spy(recorder.clone(), "sender_1")
+        .with_rate_limit(RateLimit::interval(Duration::from_millis(2)));
+    let event_handler_countdown =
+        mock::countdown(3).with_rate_limit(RateLimit::interval(Duration::from_millis(3)));
+
+    let event_loop = EventLoop::new(vec![
+        event_handler_countdown,
+        event_handler_send_1,
+        event_handler_send_0,
+    ]);
+
+    smol::run(event_loop.run())?;
+
+    let count_0 = recorder.count("sender_0");
+    let count_1 = recorder.count("sender_1");
+
+    assert!(count_0 >= 8, "count_0: {}", count_0);
+    assert!(count_1 >= 2, "count_1: {}", count_1);
+    assert!(count_1 <= 6, "count_1: {}", count_1);
+
+    Ok(())
+}
+
+#[test]
+fn run_runs_non_send_event_handlers_with_send_event_handlers() -> Result<(), SendError<()>> {
+    let recorder = Recorder::new();
+    let event_handler_send = mock::spy(recorder.clone(), "sender");
+
+    // `Rc` is `!Send`, and is held across an `.await` point.
+    let count = Rc::new(Cell::new(0));
+    let event_handler_local = {
+        let count = count.clone();
+        EventHandler::<SendError<()>>::new(move || {
+            let count = count.clone();
+            async move {
+                async {}.await;
+                count.set(count.get() + 1);
+                Ok(EventHandlingOutcome::Continue)
+            }
+        })
+    };
+
+    let event_loop = EventLoop::new(vec![
+        event_handler_send,
+        event_handler_local,
+        mock::countdown(3),
+    ]);
+
+    smol::run(event_loop.run())?;
+
+    recorder.assert_count("sender", 3);
+    assert_eq!(3, count.get());
+
+    Ok(())
+}
+
+#[test]
+fn run_runs_sync_event_handlers_with_async_event_handlers() -> Result<(), SendError<()>> {
+    let recorder = Recorder::new();
+    let event_handler_send = mock::spy(recorder.clone(), "sender");
+
+    let mut count = 3;
+    let event_handler_countdown_sync = EventHandler::<SendError<()>>::new_sync(move || {
+        count -= 1;
+        if count > 0 {
+            Ok(EventHandlingOutcome::Continue)
+        } else {
+            Ok(EventHandlingOutcome::Exit)
+        }
+    });
+
+    let event_loop = EventLoop::new(vec![event_handler_send, event_handler_countdown_sync]);
+
+    smol::run(event_loop.run())?;
+
+    recorder.assert_count("sender", 3);
+
+    Ok(())
+}
+
+#[test]
+fn run_resumes_coroutine_event_handlers_at_frame_boundaries() -> Result<(), SendError<()>> {
+    let recorder = Recorder::new();
+    let event_handler_send = mock::spy(recorder.clone(), "sender");
+
+    let frames_seen = Rc::new(RefCell::new(Vec::new()));
+    let event_handler_coroutine = {
+        let frames_seen = frames_seen.clone();
+        EventHandler::<SendError<()>>::new_coroutine(move |frames| async move {
+            frames_seen.borrow_mut().push(frames.frame());
+            frames.next_frame().await;
+            frames_seen.borrow_mut().push(frames.frame());
+            frames.wait_frames(2).await;
+            frames_seen.borrow_mut().push(frames.frame());
+
+            Ok(EventHandlingOutcome::Exit)
+        })
+    };
+
+    let event_loop = EventLoop::new(vec![event_handler_send, event_handler_coroutine]);
+
+    smol::run(event_loop.run())?;
+
+    assert_eq!(vec![0, 1, 3], *frames_seen.borrow());
+    recorder.assert_count("sender", 4);
+
+    Ok(())
+}
+
+#[test]
+fn run_continues_after_coroutine_event_handler_completes() -> Result<(), SendError<()>> {
+    let event_handler_coroutine =
+        EventHandler::<SendError<()>>::new_coroutine(|frames| async move {
+            frames.next_frame().await;
+            Ok(EventHandlingOutcome::Continue)
+        });
+
+    let event_loop = EventLoop::new(vec![event_handler_coroutine, mock::countdown(5)]);
+
+    smol::run(event_loop.run())
+}
+
+#[test]
+fn run_resumes_coroutine_event_handler_after_wait_duration() -> Result<(), SendError<()>> {
+    let start = Instant::now();
+    let event_handler_coroutine =
+        EventHandler::<SendError<()>>::new_coroutine(|frames| async move {
+            frames.wait(Duration::from_millis(10)).await;
+            Ok(EventHandlingOutcome::Exit)
+        });
+
+    let event_loop = EventLoop::new(vec![event_handler_coroutine]);
+
+    smol::run(event_loop.run())?;
+
+    assert!(start.elapsed() >= Duration::from_millis(10));
+
+    Ok(())
+}
+
+#[test]
+fn run_does_not_run_coroutine_event_handler_while_waiting() -> Result<(), SendError<()>> {
+    let frame_resumed = Rc::new(Cell::new(None));
+    let event_handler_coroutine = {
+        let frame_resumed = frame_resumed.clone();
+        EventHandler::<SendError<()>>::new_coroutine(move |frames| async move {
+            frames.wait(Duration::from_millis(20)).await;
+            frame_resumed.set(Some(frames.frame()));
+            Ok(EventHandlingOutcome::Exit)
+        })
+    };
+
+    let event_loop = EventLoop::new(vec![event_handler_coroutine]);
+
+    smol::run(event_loop.run())?;
+
+    // The coroutine is started on frame 0, and only run again once it is woken.
+    assert_eq!(Some(1), frame_resumed.get());
+
+    Ok(())
+}
+
+#[test]
+fn run_runs_stream_event_handlers_once_per_item() -> Result<(), SendError<()>> {
+    let recorder = Recorder::new();
+    let event_handler_send = mock::spy(recorder.clone(), "sender");
+
+    let items = Rc::new(RefCell::new(Vec::new()));
+    let event_handler_stream = {
+        let items = items.clone();
+        EventHandler::<SendError<()>>::from_stream(stream::iter(vec![1, 2, 3]), move |item| {
+            items.borrow_mut().push(item);
+            async move {
+                if item < 3 {
+                    Ok(EventHandlingOutcome::Continue)
+                } else {
+                    Ok(EventHandlingOutcome::Exit)
+                }
+            }
+        })
+    };
+
+    let event_loop = EventLoop::new(vec![event_handler_send, event_handler_stream]);
+
+    smol::run(event_loop.run())?;
+
+    assert_eq!(vec![1, 2, 3], *items.borrow());
+    assert!(recorder.count("sender") >= 3);
+
+    Ok(())
+}
+
+#[test]
+fn run_wakes_stream_event_handlers_when_stream_has_item() -> Result<(), SendError<()>> {
+    let ticks = stream::unfold(0, |tick| async move {
+        Timer::after(Duration::from_millis(5)).await;
+        Some((tick, tick + 1))
+    });
+    let event_handler_stream =
+        EventHandler::<SendError<()>>::from_stream(ticks, |tick| async move {
+            if tick < 2 {
+                Ok(EventHandlingOutcome::Continue)
+            } else {
+                Ok(EventHandlingOutcome::Exit)
+            }
+        });
+
+    let event_loop = EventLoop::new(vec![event_handler_stream]);
+
+    smol::run(event_loop.run())
+}
+
+#[test]
+fn run_returns_when_every_stream_event_handler_has_ended() -> Result<(), SendError<()>> {
+    let (done_tx, done_rx) = crossbeam_channel::bounded(1);
+
+    // Run in a thread, so that the test fails instead of hanging if the event loop waits
+    // for an ended stream.
+    thread::spawn(move || {
+        let items_handled = Rc::new(Cell::new(0));
+        let event_handler_stream = {
+            let items_handled = items_handled.clone();
+            EventHandler::<SendError<()>>::from_stream(stream::iter(0..3), move |_item| {
+                items_handled.set(items_handled.get() + 1);
+                async { Ok(EventHandlingOutcome::Continue) }
+            })
+        };
+        let event_handler_coroutine =
+            EventHandler::<SendError<()>>::new_coroutine(|frames| async move {
+                frames.wait_frames(2).await;
+                Ok(EventHandlingOutcome::Continue)
+            });
+
+        let event_loop = EventLoop::new(vec![event_handler_stream, event_handler_coroutine]);
+        let run_result = smol::run(event_loop.run());
+
+        let _ = done_tx.send(run_result.map(|()| items_handled.get()));
+    });
+
+    let items_handled = done_rx
+        .recv_timeout(Duration::from_secs(5))
+        .expect("Event loop did not return after every stream ended.")?;
+    assert_eq!(3, items_handled);
+
+    Ok(())
+}
+
+#[test]
+fn run_returns_when_there_are_no_event_handlers() -> Result<(), SendError<()>> {
+    let (done_tx, done_rx) = crossbeam_channel::bounded(1);
+
+    // Run in a thread, so that the test fails instead of hanging if the event loop spins
+    // with nothing to run.
+    thread::spawn(move || {
+        let event_loop = EventLoop::<SendError<()>>::new(vec![]);
+        let _ = done_tx.send(smol::run(event_loop.run()));
+    });
+
+    done_rx
+        .recv_timeout(Duration::from_secs(5))
+        .expect("Event loop did not return without event handlers.")
+}
+
+#[test]
+fn run_delivers_events_between_event_handlers() -> Result<(), SendError<()>> {
+    let event_loop = EventLoop::new(Vec::new());
+    let event_bus = event_loop.event_bus();
+
+    let writer = event_bus.writer::<u32>();
+    let mut tick = 0;
+    let event_handler_emit = EventHandler::<SendError<()>>::new_sync(move || {
+        writer.emit(tick);
+        tick += 1;
+        Ok(EventHandlingOutcome::Continue)
+    });
+
+    let mut reader = event_bus.reader::<u32>();
+    let events_read = Rc::new(RefCell::new(Vec::new()));
+    let event_handler_read = {
+        let events_read = events_read.clone();
+        EventHandler::<SendError<()>>::new_sync(move || {
+            let mut events_read = events_read.borrow_mut();
+            events_read.extend(reader.read());
+            if events_read.len() < 3 {
+                Ok(EventHandlingOutcome::Continue)
+            } else {
+                Ok(EventHandlingOutcome::Exit)
+            }
+        })
+    };
+
+    let event_loop = event_loop
+        .with_event_handler(event_handler_emit)
+        .with_event_handler(event_handler_read);
+
+    smol::run(event_loop.run())?;
+
+    assert_eq!(vec![0, 1, 2], *events_read.borrow());
+
+    Ok(())
+}
+
+#[test]
+fn run_delivers_user_events_from_proxy_to_event_handlers() -> Result<(), SendError<()>> {
+    let event_loop = EventLoop::new_with_event::<u32>(Vec::new());
+    let event_loop_proxy = event_loop.create_proxy();
+    let event_handler_user_event = EventHandler::<SendError<()>>::from_stream(
+        event_loop.event_bus().reader::<u32>(),
+        |user_event| async move {
+            if user_event < 2 {
+                Ok(EventHandlingOutcome::Continue)
+            } else {
+                Ok(EventHandlingOutcome::Exit)
+            }
+        },
+    );
+    let event_loop = event_loop.with_event_handler(event_handler_user_event);
+
+    let sender_thread = thread::spawn(move || {
+        (0..3).try_for_each(|user_event| {
+            thread::sleep(Duration::from_millis(2));
+            event_loop_proxy.send_event(user_event)
+        })
+    });
+
+    smol::run(event_loop.run())?;
+
+    assert_eq!(
+        Ok(()),
+        sender_thread.join().expect("Sender thread panicked.")
+    );
+
+    Ok(())
+}
+
+#[test]
+fn proxy_returns_user_event_when_event_loop_has_exited() {
+    let event_loop = EventLoop::new_with_event::<u32>(vec![mock::countdown::<SendError<()>>(1)]);
+    let event_loop_proxy = event_loop.create_proxy();
+
+    smol::run(event_loop.run()).expect("Event loop exits without error.");
+
+    assert_eq!(Err(EventLoopClosed(1)), event_loop_proxy.send_event(1));
+}
+
+#[test]
+fn run_skips_event_handlers_in_disabled_groups() -> Result<(), SendError<()>> {
+    let event_loop = EventLoop::new(Vec::new());
+    let handler_groups = event_loop.handler_groups();
+
+    let gameplay_count = Rc::new(Cell::new(0));
+    let event_handler_gameplay = {
+        let gameplay_count = gameplay_count.clone();
+        EventHandler::<SendError<()>>::new_sync(move || {
+            gameplay_count.set(gameplay_count.get() + 1);
+            Ok(EventHandlingOutcome::Continue)
+        })
+        .with_group("gameplay")
+    };
+
+    let mut tick = 0;
+    let event_handler_pause = EventHandler::<SendError<()>>::new_sync(move || {
+        tick += 1;
+        match tick {
+            3 => handler_groups.disable("gameplay"),
+            5 => handler_groups.enable("gameplay"),
+            6 => return Ok(EventHandlingOutcome::Exit),
+            _ => {}
+        }
+        Ok(EventHandlingOutcome::Continue)
+    });
+
+    let event_loop = event_loop
+        .with_event_handler(event_handler_gameplay)
+        .with_event_handler(event_handler_pause);
+
+    smol::run(event_loop.run())?;
+
+    assert_eq!(4, gameplay_count.get());
+
+    Ok(())
+}
+
+#[test]
+fn run_wakes_when_group_is_enabled_while_every_group_is_disabled() -> Result<(), SendError<()>> {
+    let (done_tx, done_rx) = crossbeam_channel::bounded(1);
+
+    // Run in a thread, so that the test fails instead of hanging if the event loop is not
+    // woken.
+    thread::spawn(move || {
+        let event_loop = EventLoop::new(Vec::new());
+        let handler_groups = event_loop.handler_groups();
+
+        let event_handler_gameplay = mock::countdown(3).with_group("gameplay");
+        let event_handler_menu =
+            EventHandler::<SendError<()>>::new_sync(|| Ok(EventHandlingOutcome::Continue))
+                .with_group("menu");
+
+        handler_groups.disable("gameplay");
+        handler_groups.disable("menu");
+
+        let event_loop = event_loop
+            .with_event_handler(event_handler_gameplay)
+            .with_event_handler(event_handler_menu);
+
+        let enable_gameplay = async move {
+            Timer::after(Duration::from_millis(10)).await;
+            handler_groups.enable("gameplay");
+        };
+        let (run_result, ()) = smol::run(futures::future::join(event_loop.run(), enable_gameplay));
+
+        let _ = done_tx.send(run_result);
+    });
+
+    done_rx
+        .recv_timeout(Duration::from_secs(5))
+        .expect("Event loop was not woken when a group was enabled.")
+}
+
+#[test]
+fn run_runs_event_handlers_of_current_state() -> Result<(), SendError<()>> {
+    let event_loop = EventLoop::new(Vec::new());
+    let state_stack = event_loop.state_stack();
+    let log = Rc::new(RefCell::new(Vec::new()));
+    let logger = |entry: &'static str| {
+        let log = log.clone();
+        move || log.borrow_mut().push(entry)
+    };
+
+    let state_menu = {
+        let (log, state_stack) = (log.clone(), state_stack.clone());
+        State::new("menu")
+            .with_event_handler(EventHandler::<SendError<()>>::new_sync(move || {
+                log.borrow_mut().push("menu");
+                state_stack.switch("playing");
+                Ok(EventHandlingOutcome::Continue)
+            }))
+            .with_on_enter(logger("enter menu"))
+            .with_on_exit(logger("exit menu"))
+    };
+    let state_playing = {
+        let (log, state_stack) = (log.clone(), state_stack.clone());
+        let mut tick = 0;
+        State::new("playing")
+            .with_event_handler(EventHandler::<SendError<()>>::new_sync(move || {
+                log.borrow_mut().push("playing");
+                tick += 1;
+                match tick {
+                    2 => state_stack.push("paused"),
+                    3 => return Ok(EventHandlingOutcome::Exit),
+                    _ => {}
+                }
+                Ok(EventHandlingOutcome::Continue)
+            }))
+            .with_on_enter(logger("enter playing"))
+            .with_on_pause(logger("pause playing"))
+            .with_on_resume(logger("resume playing"))
+    };
+    let state_paused = {
+        let (log, state_stack) = (log.clone(), state_stack.clone());
+        State::new("paused")
+            .with_event_handler(EventHandler::<SendError<()>>::new_sync(move || {
+                log.borrow_mut().push("paused");
+                state_stack.pop();
+                Ok(EventHandlingOutcome::Continue)
+            }))
+            .with_on_enter(logger("enter paused"))
+            .with_on_exit(logger("exit paused"))
+    };
+
+    let event_loop = event_loop
+        .with_state(state_menu)
+        .with_state(state_playing)
+        .with_state(state_paused);
+    state_stack.push("menu");
+
+    smol::run(event_loop.run())?;
+
+    assert_eq!(
+        vec![
+            "enter menu",
+            "menu",
+            "exit menu",
+            "enter playing",
+            "playing",
+            "playing",
+            "pause playing",
+            "enter paused",
+            "paused",
+            "exit paused",
+            "resume playing",
+            "playing",
+        ],
+        *log.borrow()
+    );
+
+    Ok(())
+}
+
+#[test]
+fn run_returns_boxed_error_from_event_handlers_with_different_error_types() {
+    let recorder = Recorder::new();
+    let event_handler_send = mock::spy::<SendError<()>, _>(recorder.clone(), "sender").boxed_err();
+    let event_handler_io = EventHandler::<io::Error>::new_sync(|| {
+        Err(io::Error::new(
+            io::ErrorKind::ConnectionAborted,
+            "disconnected",
+        ))
+    })
+    .boxed_err();
+
+    let event_loop = EventLoop::new(vec![event_handler_send, event_handler_io]);
+
+    let error = smol::run(event_loop.run()).unwrap_err();
+
+    assert_eq!(
+        Some(io::ErrorKind::ConnectionAborted),
+        error.downcast_ref::<io::Error>().map(io::Error::kind)
+    );
+}
+
+#[test]
+fn run_returns_converted_error_from_event_handlers_with_different_error_types() {
+    let event_handler_io = EventHandler::<io::Error>::new(|| async move {
+        Err(io::Error::new(
+            io::ErrorKind::ConnectionAborted,
+            "disconnected",
+        ))
+    })
+    .err_into::<LoopError>();
+
+    let event_loop = EventLoop::new(vec![
+        mock::countdown::<SendError<()>>(3).err_into(),
+        event_handler_io,
+    ]);
+
+    let error = smol::run(event_loop.run()).unwrap_err();
+
+    assert!(matches!(error, LoopError::Io(_)), "error: {:?}", error);
+}
+
+#[test]
+fn run_yields_to_executor_between_ticks() -> Result<(), SendError<()>> {
+    let (done_tx, done_rx) = crossbeam_channel::bounded(1);
+    thread::spawn(move || {
+        let other_task_ran = Arc::new(AtomicBool::new(false));
+        let event_handler_wait = {
+            let other_task_ran = other_task_ran.clone();
+            EventHandler::<SendError<()>>::new(move || {
+                let outcome = if other_task_ran.load(Ordering::SeqCst) {
+                    EventHandlingOutcome::Exit
+                } else {
+                    EventHandlingOutcome::Continue
+                };
+                async move { Ok(outcome) }
+            })
+        };
+
+        let event_loop = EventLoop::new(vec![event_handler_wait]);
+        let other_task = async move { other_task_ran.store(true, Ordering::SeqCst) };
+
+        // The other task only runs if the event loop returns control to the executor.
+        let (result, ()) = smol::run(futures::future::join(event_loop.run(), other_task));
+        let _ = done_tx.send(result);
+    });
+
+    done_rx
+        .recv_timeout(Duration::from_secs(5))
+        .expect("Expected event loop to yield to the executor between ticks.")
+}
+
+#[cfg(feature = "rate_limit")]
+#[test]
+fn run_with_idle_strategy_runs_rate_limited_event_handlers() -> Result<(), SendError<()>> {
+    let idle_strategies = [
+        IdleStrategy::Yield,
+        IdleStrategy::Sleep(Duration::from_millis(1)),
+        IdleStrategy::ParkUntilDeadline,
+    ];
+
+    idle_strategies
+        .iter()
+        .copied()
+        .try_for_each(|idle_strategy| {
+            let recorder = Recorder::new();
+            let event_handler_send = mock::spy(recorder.clone(), "sender")
+                .with_rate_limit(RateLimit::interval(Duration::from_millis(2)));
+            let event_handler_countdown =
+                mock::countdown(3).with_rate_limit(RateLimit::interval(Duration::from_millis(3)));
+
+            let event_loop = EventLoop::new(vec![event_handler_countdown, event_handler_send])
+                .with_idle_strategy(idle_strategy);
+
+            smol::run(event_loop.run())?;
+
+            let count = recorder.count("sender");
+            assert!(count >= 2, "{:?} count: {}", idle_strategy, count);
+            assert!(count <= 6, "{:?} count: {}", idle_strategy, count);
+
+            Ok(())
+        })
+}
+
+#[test]
+fn introspection_lists_event_handlers_before_running() -> Result<(), SendError<()>> {
+    let recorder = Recorder::new();
+    let event_loop = EventLoop::new(vec![mock::spy(recorder.clone(), "sender")]);
+    let introspection = event_loop.introspection();
+    let handler_groups = event_loop.handler_groups();
+    let event_loop = event_loop.with_event_handler(mock::countdown(2).with_group("gameplay"));
+
+    let handler_infos = introspection.handlers();
+    let names = handler_infos
+        .iter()
+        .map(|handler_info| handler_info.name.as_ref())
+        .collect::<Vec<_>>();
+    assert_eq!(vec!["sender", "countdown"], names);
+    assert!(handler_infos
+        .iter()
+        .all(|handler_info| handler_info.last_run.is_none()));
+
+    smol::run(event_loop.run())?;
+    handler_groups.disable("gameplay");
+
+    // `Introspection` is `Send`, so it may be read from another thread.
+    let handler_infos = thread::spawn(move || introspection.handlers())
+        .join()
+        .expect("Introspection thread panicked.");
+    assert!(handler_infos[0].is_enabled);
+    assert!(!handler_infos[1].is_enabled);
+    assert!(handler_infos
+        .iter()
+        .all(|handler_info| handler_info.last_run.is_some()));
+
+    Ok(())
+}
+
+#[test]
+fn run_returns_when_stop_is_requested() -> Result<(), SendError<()>> {
+    let recorder = Recorder::new();
+    let event_loop = EventLoop::new(vec![mock::spy(recorder.clone(), "sender")]);
+    let stop_handle = event_loop.stop_handle();
+    let event_loop = event_loop.with_event_handler(mock::stopper(stop_handle.clone(), 3));
+
+    smol::run(event_loop.run())?;
+
+    recorder.assert_count("sender", 3);
+    assert_eq!(Some(String::from("done")), stop_handle.reason());
+
+    Ok(())
+}
+
+#[cfg(feature = "rate_limit")]
+#[test]
+fn run_wakes_from_idle_when_stop_is_requested() -> Result<(), SendError<()>> {
+    let recorder = Recorder::new();
+    let event_handler_send = mock::spy(recorder.clone(), "sender")
+        .with_rate_limit(RateLimit::interval(Duration::from_secs(60)));
+
+    let event_loop = EventLoop::new(vec![event_handler_send]);
+    let stop_handle = event_loop.stop_handle();
+
+    let stop_thread = std::thread::spawn(move || {
+        std::thread::sleep(Duration::from_millis(10));
+        stop_handle.stop();
+    });
+
+    let start = std::time::Instant::now();
+    smol::run(event_loop.run())?;
+    stop_thread.join().expect("Stop thread panicked.");
+
+    assert!(start.elapsed() < Duration::from_secs(30));
+    recorder.assert_count("sender", 1);
+
+    Ok(())
+}
+
+#[derive(Debug)]
+enum LoopError {
+    Io(io::Error),
+    Send(SendError<()>),
+}
+
+impl fmt::Display for LoopError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Io(error) => error.fmt(f),
+            Self::Send(error) => error.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for LoopError {}
+
+impl From<io::Error> for LoopError {
+    fn from(error: io::Error) -> Self {
+        Self::Io(error)
+    }
+}
+
+impl From<SendError<()>> for LoopError {
+    fn from(error: SendError<()>) -> Self {
+        Self::Send(error)
+    }
+}
@@ -0,0 +1,329 @@
+#![cfg(feature = "window")]
+
+#[cfg(feature = "rate_limit")]
+use std::num::NonZeroU32;
+use std::{thread, time::Duration};
+
+use crossbeam_channel::SendError;
+use futures::stream;
+use instant::Instant;
+use nginee_event_loop::{EventHandler, EventHandlingOutcome, EventLoop, ExitReason};
+#[cfg(feature = "rate_limit")]
+use nginee_event_loop::{FramePacing, RateLimit};
+use nginee_test::{mock, Recorder};
+
+#[test]
+fn run_runs_event_handlers_until_exit_is_signalled() {
+    let recorder = Recorder::new();
+
+    let exit_reason = nginee_test::run_ticks(3, {
+        let recorder = recorder.clone();
+        move || EventLoop::new_any_thread(vec![mock::spy(recorder, "sender")])
+    });
+
+    assert_eq!(ExitReason::<SendError<()>>::Exit, exit_reason);
+    recorder.assert_count("sender", 3);
+}
+
+#[test]
+fn run_returns_on_first_error() {
+    let recorder = Recorder::new();
+
+    let exit_reason = nginee_test::run_ticks(3, {
+        let recorder = recorder.clone();
+        move || {
+            EventLoop::new_any_thread(vec![
+                mock::spy(recorder, "sender"),
+                mock::errorer(SendError(())),
+            ])
+        }
+    });
+
+    assert_eq!(ExitReason::Error(SendError(())), exit_reason);
+    recorder.assert_count("sender", 1);
+}
+
+#[test]
+fn run_passes_stop_reason_to_exit_handler() {
+    let recorder = Recorder::new();
+
+    let exit_reason = nginee_test::run_ticks(10, {
+        let recorder = recorder.clone();
+        move || {
+            let event_loop =
+                EventLoop::<SendError<()>>::new_any_thread(vec![mock::spy(recorder, "sender")]);
+            let stop_handle = event_loop.stop_handle();
+            event_loop.with_event_handler(mock::stopper(stop_handle, 3))
+        }
+    });
+
+    assert_eq!(ExitReason::Stopped(Some(String::from("done"))), exit_reason);
+    recorder.assert_count("sender", 3);
+}
+
+#[test]
+fn run_runs_stream_event_handlers_once_per_item() -> Result<(), SendError<()>> {
+    let (done_tx, done_rx) = crossbeam_channel::bounded(1);
+    let (tx, rx) = crossbeam_channel::unbounded();
+
+    thread::spawn(|| {
+        let event_handler_stream =
+            EventHandler::<SendError<()>>::from_stream(stream::iter(vec![1, 2, 3]), move |item| {
+                let tx = tx.clone();
+                async move {
+                    tx.send(item).map_err(|_| SendError(()))?;
+
+                    if item < 3 {
+                        Ok(EventHandlingOutcome::Continue)
+                    } else {
+                        Ok(EventHandlingOutcome::Exit)
+                    }
+                }
+            });
+
+        let event_loop = EventLoop::new_any_thread(vec![event_handler_stream]).with_exit_handler(
+            |e| async move {
+                let _ = done_tx.send(e);
+            },
+        );
+
+        smol::run(event_loop.run());
+    });
+
+    let run_result = done_rx.recv();
+    assert_eq!(Ok(ExitReason::Exit), run_result);
+    assert_eq!(vec![1, 2, 3], rx.try_iter().collect::<Vec<_>>());
+
+    Ok(())
+}
+
+#[test]
+fn run_exits_when_every_stream_event_handler_has_ended() -> Result<(), SendError<()>> {
+    let (done_tx, done_rx) = crossbeam_channel::bounded(1);
+    let (tx, rx) = crossbeam_channel::unbounded();
+
+    thread::spawn(|| {
+        let event_handler_stream =
+            EventHandler::<SendError<()>>::from_stream(stream::iter(vec![1, 2, 3]), move |item| {
+                let tx = tx.clone();
+                async move {
+                    tx.send(item).map_err(|_| SendError(()))?;
+                    Ok(EventHandlingOutcome::Continue)
+                }
+            });
+
+        let event_loop = EventLoop::new_any_thread(vec![event_handler_stream]).with_exit_handler(
+            |e| async move {
+                let _ = done_tx.send(e);
+            },
+        );
+
+        smol::run(event_loop.run());
+    });
+
+    let run_result = done_rx.recv_timeout(Duration::from_secs(5));
+    assert_eq!(Ok(ExitReason::Exit), run_result);
+    assert_eq!(vec![1, 2, 3], rx.try_iter().collect::<Vec<_>>());
+
+    Ok(())
+}
+
+#[test]
+fn run_delivers_user_events_from_proxy_to_event_handlers() -> Result<(), SendError<()>> {
+    let (done_tx, done_rx) = crossbeam_channel::bounded(1);
+    let (proxy_tx, proxy_rx) = crossbeam_channel::bounded(1);
+
+    thread::spawn(move || {
+        let event_loop = EventLoop::new_any_thread(Vec::new());
+        let _ = proxy_tx.send(event_loop.create_proxy());
+        let event_handler_user_event = EventHandler::<SendError<()>>::from_stream(
+            event_loop.event_bus().reader::<()>(),
+            |()| async { Ok(EventHandlingOutcome::Exit) },
+        );
+
+        let event_loop = event_loop
+            .with_event_handler(event_handler_user_event)
+            .with_exit_handler(|e| async move {
+                let _ = done_tx.send(e);
+            });
+
+        smol::run(event_loop.run());
+    });
+
+    let event_loop_proxy = proxy_rx.recv().expect("Event loop thread panicked.");
+    thread::sleep(Duration::from_millis(10));
+    assert_eq!(Ok(()), event_loop_proxy.send_event(()));
+
+    let run_result = done_rx.recv_timeout(Duration::from_secs(5));
+    assert_eq!(Ok(ExitReason::Exit), run_result);
+
+    Ok(())
+}
+
+#[test]
+fn run_resumes_coroutine_event_handlers_at_frame_boundaries() -> Result<(), SendError<()>> {
+    let (done_tx, done_rx) = crossbeam_channel::bounded(1);
+    let (tx, rx) = crossbeam_channel::unbounded();
+
+    thread::spawn(|| {
+        let event_handler_coroutine =
+            EventHandler::<SendError<()>>::new_coroutine(move |frames| async move {
+                tx.send(frames.frame()).map_err(|_| SendError(()))?;
+                frames.next_frame().await;
+                tx.send(frames.frame()).map_err(|_| SendError(()))?;
+                frames.wait_frames(2).await;
+                tx.send(frames.frame()).map_err(|_| SendError(()))?;
+
+                Ok(EventHandlingOutcome::Exit)
+            });
+
+        let event_loop = EventLoop::new_any_thread(vec![event_handler_coroutine])
+            .with_exit_handler(|e| async move {
+                let _ = done_tx.send(e);
+            });
+
+        smol::run(event_loop.run());
+    });
+
+    let run_result = done_rx.recv();
+    assert_eq!(Ok(ExitReason::Exit), run_result);
+    assert_eq!(vec![0, 1, 3], rx.try_iter().collect::<Vec<_>>());
+
+    Ok(())
+}
+
+#[test]
+fn run_resumes_coroutine_event_handler_after_wait_duration() -> Result<(), SendError<()>> {
+    let (done_tx, done_rx) = crossbeam_channel::bounded(1);
+    let (tx, rx) = crossbeam_channel::unbounded();
+    let start = Instant::now();
+
+    thread::spawn(|| {
+        let event_handler_coroutine =
+            EventHandler::<SendError<()>>::new_coroutine(move |frames| async move {
+                frames.wait(Duration::from_millis(10)).await;
+                tx.send(frames.frame()).map_err(|_| SendError(()))?;
+
+                Ok(EventHandlingOutcome::Exit)
+            });
+
+        let event_loop = EventLoop::new_any_thread(vec![event_handler_coroutine])
+            .with_exit_handler(|e| async move {
+                let _ = done_tx.send(e);
+            });
+
+        smol::run(event_loop.run());
+    });
+
+    let run_result = done_rx.recv();
+    assert_eq!(Ok(ExitReason::Exit), run_result);
+    assert!(start.elapsed() >= Duration::from_millis(10));
+    // The coroutine is started on frame 0, and only run again once it is woken.
+    assert_eq!(vec![1], rx.try_iter().collect::<Vec<_>>());
+
+    Ok(())
+}
+
+#[cfg(feature = "rate_limit")]
+#[test]
+fn event_handlers_are_rate_limited_independently() -> Result<(), SendError<()>> {
+    let (done_tx, done_rx) = crossbeam_channel::bounded(1);
+    let recorder = Recorder::new();
+
+    thread::spawn({
+        let recorder = recorder.clone();
+        move || {
+            let event_handler_send_0 = mock::spy::<SendError<()>, _>(recorder.clone(), "sender_0");
+
+            let event_handler_send_1 = mock::spy(recorder, "sender_1")
+                .with_rate_limit(RateLimit::interval(Duration::from_millis(2)));
+            let event_handler_countdown =
+                mock::countdown(3).with_rate_limit(RateLimit::interval(Duration::from_millis(3)));
+
+            let event_loop = EventLoop::new_any_thread(vec![
+                event_handler_countdown,
+                event_handler_send_1,
+                event_handler_send_0,
+            ])
+            .with_exit_handler(|e| async move {
+                let _ = done_tx.send(e);
+            });
+            smol::run(event_loop.run());
+        }
+    });
+
+    let _ = done_rx.recv();
+
+    let count_0 = recorder.count("sender_0");
+    let count_1 = recorder.count("sender_1");
+
+    assert!(count_0 >= 8, "count_0: {}", count_0);
+    assert!(count_1 >= 2, "count_1: {}", count_1);
+    assert!(count_1 <= 6, "count_1: {}", count_1);
+
+    Ok(())
+}
+
+#[cfg(feature = "rate_limit")]
+#[test]
+fn frame_paced_event_handlers_run_once_per_frame() -> Result<(), SendError<Instant>> {
+    let (done_tx, done_rx) = crossbeam_channel::bounded(1);
+    let (tx, rx) = crossbeam_channel::unbounded();
+
+    thread::spawn(|| {
+        let event_handler_send = EventHandler::<SendError<Instant>>::new(move || {
+            let tx = tx.clone();
+            async move {
+                tx.send(Instant::now())?;
+
+                Ok(EventHandlingOutcome::Continue)
+            }
+        })
+        .with_frame_pacing(FramePacing::RefreshRate(
+            NonZeroU32::new(500).expect("500 is non-zero."),
+        ));
+        let event_handler_exit = EventHandler::<SendError<Instant>>::new_sync({
+            let mut count = 3u32;
+            move || {
+                count = count.saturating_sub(1);
+                if count > 0 {
+                    Ok(EventHandlingOutcome::Continue)
+                } else {
+                    Ok(EventHandlingOutcome::Exit)
+                }
+            }
+        })
+        .with_rate_limit(RateLimit::interval(Duration::from_millis(10)));
+
+        let event_loop = EventLoop::new_any_thread(vec![event_handler_exit, event_handler_send])
+            .with_exit_handler(|e| async move {
+                let _ = done_tx.send(e);
+            });
+        smol::run(event_loop.run());
+    });
+
+    let _ = done_rx.recv();
+
+    // Frames are aligned to a 2 ms grid, so consecutive frames are never closer
+    // than one frame apart, and there is at most one frame per 2 ms elapsed.
+    let frame_interval = Duration::from_millis(2);
+    let run_instants = rx.try_iter().collect::<Vec<Instant>>();
+    assert!(run_instants.len() >= 2, "runs: {}", run_instants.len());
+    run_instants.windows(2).for_each(|pair| {
+        assert!(
+            pair[1] - pair[0] >= frame_interval,
+            "frame spacing: {:?}",
+            pair[1] - pair[0]
+        )
+    });
+    let elapsed = run_instants[run_instants.len() - 1] - run_instants[0];
+    let frames_max = elapsed.as_nanos() / frame_interval.as_nanos() + 1;
+    assert!(
+        run_instants.len() as u128 <= frames_max,
+        "runs: {}, elapsed: {:?}",
+        run_instants.len(),
+        elapsed
+    );
+
+    Ok(())
+}
@@ -0,0 +1,16 @@
+//! Sets `cfg(nginee_event_loop_window)` when `nginee_event_loop` is built with
+//! its `"window"` feature.
+//!
+//! Cargo does not forward features to dev-dependencies, so `run_ticks` is
+//! selected from the features of the `nginee_event_loop` that is built, rather
+//! than from this crate's features.
+
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+    println!("cargo:rerun-if-env-changed=DEP_NGINEE_EVENT_LOOP_WINDOW");
+    println!("cargo:rustc-check-cfg=cfg(nginee_event_loop_window)");
+
+    if std::env::var_os("DEP_NGINEE_EVENT_LOOP_WINDOW").is_some() {
+        println!("cargo:rustc-cfg=nginee_event_loop_window");
+    }
+}
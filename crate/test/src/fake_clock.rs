@@ -0,0 +1,97 @@
+use std::{
+    error::Error,
+    sync::{Arc, Mutex, PoisonError},
+    time::Duration,
+};
+
+use instant::Instant;
+use nginee_event_loop::{EventHandler, EventHandlingOutcome};
+
+/// Clock that only moves forward when it is advanced.
+///
+/// Event handlers whose logic depends on time may read the time from this
+/// clock instead of `Instant::now`, so that tests are deterministic. This may
+/// be cloned, and clones share the same time.
+///
+/// The event loop's own rate limiting uses the system clock.
+#[derive(Clone, Debug)]
+pub struct FakeClock {
+    /// Time the clock was created.
+    start: Instant,
+    /// Duration the clock has been advanced by.
+    elapsed: Arc<Mutex<Duration>>,
+}
+
+impl FakeClock {
+    /// Returns a new `FakeClock`, starting at the current time.
+    pub fn new() -> Self {
+        Self {
+            start: Instant::now(),
+            elapsed: Arc::new(Mutex::new(Duration::from_secs(0))),
+        }
+    }
+
+    /// Returns the clock's current time.
+    pub fn now(&self) -> Instant {
+        self.start + self.elapsed()
+    }
+
+    /// Returns the duration the clock has been advanced by.
+    pub fn elapsed(&self) -> Duration {
+        *self.elapsed.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Moves the clock forward.
+    ///
+    /// # Parameters
+    ///
+    /// * `duration`: Duration to advance the clock by.
+    pub fn advance(&self, duration: Duration) {
+        *self.elapsed.lock().unwrap_or_else(PoisonError::into_inner) += duration;
+    }
+
+    /// Returns an event handler that advances this clock on each run.
+    ///
+    /// This is typically the first event handler, so that the time moves
+    /// forward by a fixed step each tick.
+    ///
+    /// # Parameters
+    ///
+    /// * `step`: Duration to advance the clock by each run.
+    pub fn ticker<E>(&self, step: Duration) -> EventHandler<E>
+    where
+        E: Error + 'static,
+    {
+        let fake_clock = self.clone();
+        EventHandler::new_sync(move || {
+            fake_clock.advance(step);
+            Ok(EventHandlingOutcome::Continue)
+        })
+        .with_name("ticker")
+    }
+}
+
+impl Default for FakeClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::FakeClock;
+
+    #[test]
+    fn advance_moves_time_forward_for_clones() {
+        let fake_clock = FakeClock::new();
+        let start = fake_clock.now();
+
+        fake_clock.clone().advance(Duration::from_secs(2));
+        fake_clock.advance(Duration::from_millis(500));
+
+        assert_eq!(Duration::from_millis(2500), fake_clock.elapsed());
+        assert_eq!(start + Duration::from_millis(2500), fake_clock.now());
+    }
+}
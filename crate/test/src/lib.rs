@@ -0,0 +1,21 @@
+//! Test utilities for `nginee` event loops.
+//!
+//! This provides mock event handlers, a `Recorder` to assert on the order and
+//! number of event handler runs, a `FakeClock` for time dependent logic, and
+//! `run_ticks` to run an event loop for a fixed number of ticks.
+
+#![deny(
+    anonymous_parameters,
+    arithmetic_overflow,
+    array_into_iter,
+    missing_debug_implementations,
+    missing_docs
+)]
+
+pub use crate::{fake_clock::FakeClock, recorder::Recorder, run_ticks::run_ticks};
+
+pub mod mock;
+
+mod fake_clock;
+mod recorder;
+mod run_ticks;
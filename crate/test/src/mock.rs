@@ -0,0 +1,92 @@
+//! Mock event handlers.
+//!
+//! These replace the ad hoc event handlers that tests would otherwise define,
+//! such as ones that exit after a number of runs, or that return an error.
+
+use std::{borrow::Cow, error::Error};
+
+use nginee_event_loop::{EventHandler, EventHandlingOutcome, StopHandle};
+
+use crate::Recorder;
+
+/// Returns an event handler that records each of its runs.
+///
+/// The event handler is named `name`, and always continues.
+///
+/// # Parameters
+///
+/// * `recorder`: Records the event handler's runs.
+/// * `name`: Name of the event handler.
+pub fn spy<E, N>(recorder: Recorder, name: N) -> EventHandler<E>
+where
+    E: Error + 'static,
+    N: Into<Cow<'static, str>>,
+{
+    let name = name.into();
+    EventHandler::new_sync({
+        let name = name.clone();
+        move || {
+            recorder.record(name.clone());
+            Ok(EventHandlingOutcome::Continue)
+        }
+    })
+    .with_name(name)
+}
+
+/// Returns an event handler that signals `Exit` on its `count`th run.
+///
+/// A `count` of `0` is treated as `1`.
+///
+/// # Parameters
+///
+/// * `count`: Number of runs before exiting.
+pub fn countdown<E>(mut count: u32) -> EventHandler<E>
+where
+    E: Error + 'static,
+{
+    EventHandler::new_sync(move || {
+        count = count.saturating_sub(1);
+        if count > 0 {
+            Ok(EventHandlingOutcome::Continue)
+        } else {
+            Ok(EventHandlingOutcome::Exit)
+        }
+    })
+    .with_name("countdown")
+}
+
+/// Returns an event handler that requests the event loop to stop on its
+/// `count`th run.
+///
+/// The stop is requested with the reason `"done"`. A `count` of `0` is treated
+/// as `1`.
+///
+/// # Parameters
+///
+/// * `stop_handle`: Handle from the event loop to stop.
+/// * `count`: Number of runs before requesting the stop.
+pub fn stopper<E>(stop_handle: StopHandle, mut count: u32) -> EventHandler<E>
+where
+    E: Error + 'static,
+{
+    EventHandler::new_sync(move || {
+        count = count.saturating_sub(1);
+        if count == 0 {
+            stop_handle.stop_with_reason("done");
+        }
+        Ok(EventHandlingOutcome::Continue)
+    })
+    .with_name("stopper")
+}
+
+/// Returns an event handler that returns the given error on every run.
+///
+/// # Parameters
+///
+/// * `error`: The error to return.
+pub fn errorer<E>(error: E) -> EventHandler<E>
+where
+    E: Clone + Error + 'static,
+{
+    EventHandler::new_sync(move || Err(error.clone())).with_name("errorer")
+}
@@ -0,0 +1,123 @@
+use std::{
+    borrow::Cow,
+    sync::{Arc, Mutex, MutexGuard, PoisonError},
+};
+
+/// Records the names of event handlers in the order they are run.
+///
+/// This is cloned into `mock::spy` event handlers, and may be sent to other
+/// threads, such as the thread that runs a `"window"` event loop.
+#[derive(Clone, Debug, Default)]
+pub struct Recorder {
+    /// Names of the event handlers that have run.
+    invocations: Arc<Mutex<Vec<Cow<'static, str>>>>,
+}
+
+impl Recorder {
+    /// Returns a new `Recorder`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records that an event handler has run.
+    ///
+    /// # Parameters
+    ///
+    /// * `name`: Name of the event handler.
+    pub fn record<N>(&self, name: N)
+    where
+        N: Into<Cow<'static, str>>,
+    {
+        self.lock().push(name.into());
+    }
+
+    /// Returns the names of the event handlers that have run, in order.
+    pub fn runs(&self) -> Vec<Cow<'static, str>> {
+        self.lock().clone()
+    }
+
+    /// Returns the number of times the named event handler has run.
+    ///
+    /// # Parameters
+    ///
+    /// * `name`: Name of the event handler.
+    pub fn count(&self, name: &str) -> usize {
+        self.lock()
+            .iter()
+            .filter(|invocation| *invocation == name)
+            .count()
+    }
+
+    /// Removes all recorded runs.
+    pub fn clear(&self) {
+        self.lock().clear();
+    }
+
+    /// Asserts that exactly the given event handlers have run, in order.
+    ///
+    /// # Parameters
+    ///
+    /// * `expected`: Names of the event handlers expected to have run.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the recorded runs differ from `expected`.
+    pub fn assert_runs(&self, expected: &[&str]) {
+        let runs = self.runs();
+        assert_eq!(expected, runs.as_slice(), "Event handler runs differ.");
+    }
+
+    /// Asserts that the named event handler has run exactly `expected` times.
+    ///
+    /// # Parameters
+    ///
+    /// * `name`: Name of the event handler.
+    /// * `expected`: Number of times the event handler is expected to have run.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the event handler has run a different number of times.
+    pub fn assert_count(&self, name: &str, expected: usize) {
+        assert_eq!(
+            expected,
+            self.count(name),
+            "Event handler `{}` run count differs.",
+            name
+        );
+    }
+
+    /// Locks the recorded runs, recovering them if another thread panicked.
+    fn lock(&self) -> MutexGuard<'_, Vec<Cow<'static, str>>> {
+        self.invocations
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Recorder;
+
+    #[test]
+    fn count_returns_runs_of_named_event_handler() {
+        let recorder = Recorder::new();
+        recorder.record("a");
+        recorder.record("b");
+        recorder.record("a");
+
+        recorder.assert_runs(&["a", "b", "a"]);
+        recorder.assert_count("a", 2);
+        recorder.assert_count("b", 1);
+        recorder.assert_count("c", 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "Event handler runs differ.")]
+    fn assert_runs_panics_when_order_differs() {
+        let recorder = Recorder::new();
+        recorder.record("a");
+        recorder.record("b");
+
+        recorder.assert_runs(&["b", "a"]);
+    }
+}
@@ -0,0 +1,226 @@
+use std::error::Error;
+#[cfg(nginee_event_loop_window)]
+use std::{sync::mpsc, thread};
+
+use nginee_event_loop::EventLoop;
+#[cfg(nginee_event_loop_window)]
+use nginee_event_loop::ExitReason;
+
+use crate::mock;
+
+/// Runs the event loop returned by `fn_event_loop` for `ticks` ticks.
+///
+/// An event handler that signals `Exit` on its `ticks`th run is added after
+/// the existing event handlers, so every event handler without a rate limit
+/// runs exactly `ticks` times, unless the event loop exits earlier. At least
+/// one tick is run.
+///
+/// # Parameters
+///
+/// * `ticks`: Number of ticks to run.
+/// * `fn_event_loop`: Returns the event loop to run.
+#[cfg(not(nginee_event_loop_window))]
+pub fn run_ticks<E, UserEvent, FnEventLoop>(ticks: u32, fn_event_loop: FnEventLoop) -> Result<(), E>
+where
+    E: Error + 'static,
+    UserEvent: 'static,
+    FnEventLoop: FnOnce() -> EventLoop<E, UserEvent>,
+{
+    let event_loop = fn_event_loop().with_event_handler(mock::countdown(ticks));
+
+    futures::executor::block_on(event_loop.run())
+}
+
+/// Runs the event loop returned by `fn_event_loop` for `ticks` ticks.
+///
+/// An event handler that signals `Exit` on its `ticks`th run is added after
+/// the existing event handlers, so every event handler without a rate limit
+/// runs exactly `ticks` times, unless the event loop exits earlier. At least
+/// one tick is run.
+///
+/// The event loop is created and run on a new thread, so `fn_event_loop`
+/// should use `EventLoop::new_any_thread`. Its exit handler is replaced, and
+/// the exit reason is returned instead.
+///
+/// # Parameters
+///
+/// * `ticks`: Number of ticks to run.
+/// * `fn_event_loop`: Returns the event loop to run.
+#[cfg(nginee_event_loop_window)]
+pub fn run_ticks<E, UserEvent, FnEventLoop>(ticks: u32, fn_event_loop: FnEventLoop) -> ExitReason<E>
+where
+    E: Error + Send + 'static,
+    UserEvent: 'static,
+    FnEventLoop: FnOnce() -> EventLoop<E, UserEvent> + Send + 'static,
+{
+    let (exit_reason_tx, exit_reason_rx) = mpsc::channel();
+
+    thread::spawn(move || {
+        let event_loop = fn_event_loop()
+            .with_event_handler(mock::countdown(ticks))
+            .with_exit_handler(move |exit_reason| {
+                let _ = exit_reason_tx.send(exit_reason);
+                async {}
+            });
+
+        futures::executor::block_on(event_loop.run());
+    });
+
+    exit_reason_rx
+        .recv()
+        .expect("Event loop thread panicked before exiting.")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fmt, time::Duration};
+
+    use nginee_event_loop::EventLoop;
+    #[cfg(nginee_event_loop_window)]
+    use nginee_event_loop::ExitReason;
+
+    use super::run_ticks;
+    use crate::{mock, FakeClock, Recorder};
+
+    #[cfg(not(nginee_event_loop_window))]
+    #[test]
+    fn run_ticks_runs_each_event_handler_once_per_tick() -> Result<(), TestError> {
+        let recorder = Recorder::new();
+
+        run_ticks(3, || {
+            EventLoop::new(vec![
+                mock::spy(recorder.clone(), "a"),
+                mock::spy(recorder.clone(), "b"),
+            ])
+        })?;
+
+        recorder.assert_runs(&["a", "b", "a", "b", "a", "b"]);
+
+        Ok(())
+    }
+
+    #[cfg(not(nginee_event_loop_window))]
+    #[test]
+    fn run_ticks_returns_error_from_event_handler() {
+        let recorder = Recorder::new();
+
+        let result = run_ticks(3, || {
+            EventLoop::new(vec![
+                mock::spy(recorder.clone(), "a"),
+                mock::errorer(TestError),
+            ])
+        });
+
+        assert_eq!(Err(TestError), result);
+        recorder.assert_count("a", 1);
+    }
+
+    #[cfg(not(nginee_event_loop_window))]
+    #[test]
+    fn run_ticks_returns_when_stopper_requests_stop() -> Result<(), TestError> {
+        let recorder = Recorder::new();
+
+        run_ticks(5, || {
+            let event_loop = EventLoop::new(vec![mock::spy(recorder.clone(), "a")]);
+            let stop_handle = event_loop.stop_handle();
+            event_loop.with_event_handler(mock::stopper(stop_handle, 2))
+        })?;
+
+        recorder.assert_count("a", 2);
+
+        Ok(())
+    }
+
+    #[cfg(not(nginee_event_loop_window))]
+    #[test]
+    fn run_ticks_advances_fake_clock_each_tick() -> Result<(), TestError> {
+        let fake_clock = FakeClock::new();
+
+        run_ticks(4, || {
+            EventLoop::new(vec![fake_clock.ticker(Duration::from_millis(10))])
+        })?;
+
+        assert_eq!(Duration::from_millis(40), fake_clock.elapsed());
+
+        Ok(())
+    }
+
+    #[cfg(nginee_event_loop_window)]
+    #[test]
+    fn run_ticks_runs_each_event_handler_once_per_tick() {
+        let recorder = Recorder::new();
+
+        let exit_reason = run_ticks(3, {
+            let recorder = recorder.clone();
+            move || {
+                EventLoop::new_any_thread(vec![
+                    mock::spy(recorder.clone(), "a"),
+                    mock::spy(recorder, "b"),
+                ])
+            }
+        });
+
+        assert_eq!(ExitReason::<TestError>::Exit, exit_reason);
+        recorder.assert_runs(&["a", "b", "a", "b", "a", "b"]);
+    }
+
+    #[cfg(nginee_event_loop_window)]
+    #[test]
+    fn run_ticks_returns_error_from_event_handler() {
+        let recorder = Recorder::new();
+
+        let exit_reason = run_ticks(3, {
+            let recorder = recorder.clone();
+            move || {
+                EventLoop::new_any_thread(vec![mock::spy(recorder, "a"), mock::errorer(TestError)])
+            }
+        });
+
+        assert_eq!(ExitReason::Error(TestError), exit_reason);
+        recorder.assert_count("a", 1);
+    }
+
+    #[cfg(nginee_event_loop_window)]
+    #[test]
+    fn run_ticks_returns_stop_reason_from_stopper() {
+        let recorder = Recorder::new();
+
+        let exit_reason = run_ticks(5, {
+            let recorder = recorder.clone();
+            move || {
+                let event_loop =
+                    EventLoop::<TestError>::new_any_thread(vec![mock::spy(recorder, "a")]);
+                let stop_handle = event_loop.stop_handle();
+                event_loop.with_event_handler(mock::stopper(stop_handle, 2))
+            }
+        });
+
+        assert_eq!(ExitReason::Stopped(Some(String::from("done"))), exit_reason);
+        recorder.assert_count("a", 2);
+    }
+
+    #[cfg(nginee_event_loop_window)]
+    #[test]
+    fn run_ticks_advances_fake_clock_each_tick() {
+        let fake_clock = FakeClock::new();
+
+        let exit_reason = run_ticks(4, {
+            let fake_clock = fake_clock.clone();
+            move || EventLoop::new_any_thread(vec![fake_clock.ticker(Duration::from_millis(10))])
+        });
+
+        assert_eq!(ExitReason::<TestError>::Exit, exit_reason);
+        assert_eq!(Duration::from_millis(40), fake_clock.elapsed());
+    }
+
+    #[derive(Clone, Copy, Debug, PartialEq)]
+    struct TestError;
+
+    impl fmt::Display for TestError {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            write!(f, "test error")
+        }
+    }
+
+    impl std::error::Error for TestError {}
+}